@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use embedded_graphics::mono_font::ascii::FONT_5X7;
+use esp_remotecontrolled_led_matrix_client::Rgb;
+use esp_remotecontrolled_led_matrix_client::async_tokio::Client;
+use esp_remotecontrolled_led_matrix_client::compositor::{BlendMode, Compositor, Layer};
+use esp_remotecontrolled_led_matrix_client::framebuffer::Framebuffer;
+use esp_remotecontrolled_led_matrix_client::ticker::Ticker;
+
+const BACKGROUND: usize = 0;
+const NOTIFICATION: usize = 1;
+const TICKER: usize = 2;
+
+#[tokio::main]
+async fn main() {
+    let addr = std::env::var("ADDR");
+    let addr = addr.as_deref().unwrap_or("espPixelmatrix:1337");
+    let client = Client::connect(addr).await.expect("connection error");
+    let (width, height) = (client.width(), client.height());
+
+    let mut notification = Layer::new(width / 2, height / 2)
+        .with_offset(i16::from(width / 4), i16::from(height / 4))
+        .with_blend_mode(BlendMode::Add);
+    notification.fill([0, 0, 255, 200]);
+
+    let ticker_height = 9;
+    let mut compositor = Compositor::new(width, height)
+        .layer(Layer::new(width, height))
+        .layer(notification)
+        .layer(
+            Layer::new(width, ticker_height)
+                .with_offset(0, i16::from(height.saturating_sub(ticker_height))),
+        );
+
+    let mut ticker = Ticker::new(&FONT_5X7).segment("Layers blended on the matrix", Rgb::WHITE);
+    let mut ticker_buffer = Framebuffer::new(width, ticker_height);
+
+    let start = Instant::now();
+    let mut interval = tokio::time::interval(Duration::from_millis(40));
+    loop {
+        interval.tick().await;
+        let elapsed = start.elapsed().as_secs_f32();
+
+        let background = &mut compositor.layers_mut()[BACKGROUND];
+        for (x, pixel) in (0..width).cycle().zip(background.pixels_mut()) {
+            let hue = f32::from(x).mul_add(4.0, elapsed * 60.0);
+            let [red, green, blue] = Rgb::from_hsv(hue, 1.0, 0.3).to_array();
+            *pixel = [red, green, blue, 255];
+        }
+
+        // Fade the notification in and out every 4 seconds
+        let phase = (elapsed / 4.0).fract();
+        let opacity = 1.0 - (phase * 2.0 - 1.0).abs();
+        compositor.layers_mut()[NOTIFICATION].set_opacity(opacity);
+
+        ticker.advance(Duration::from_millis(40));
+        ticker.draw(&mut ticker_buffer);
+        compositor.layers_mut()[TICKER].copy_keyed(ticker_buffer.pixels(), [0, 0, 0]);
+
+        client.display_composition(&compositor).await.unwrap();
+        client.flush().await.unwrap();
+    }
+}
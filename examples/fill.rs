@@ -1,5 +1,6 @@
 use std::time::Instant;
 
+use esp_remotecontrolled_led_matrix_client::Rgb;
 use esp_remotecontrolled_led_matrix_client::sync::Client;
 
 fn main() {
@@ -18,7 +19,7 @@ fn main() {
     let write = Instant::now();
 
     // Fill with RGB 0 0 255 = blue
-    client.fill(0, 0, 255).unwrap();
+    client.fill(Rgb::BLUE).unwrap();
     client.flush().unwrap();
 
     let took = write.elapsed();
@@ -1,5 +1,6 @@
 use std::time::Instant;
 
+use esp_remotecontrolled_led_matrix_client::Rgb;
 use esp_remotecontrolled_led_matrix_client::sync::Client;
 
 fn main() {
@@ -27,7 +28,7 @@ fn main() {
     // |   XXX
     // |
 
-    client.rectangle(3, 1, 3, 4, 0, 0, 255).unwrap();
+    client.rectangle(3, 1, 3, 4, Rgb::BLUE).unwrap();
     client.flush().unwrap();
 
     let took = write.elapsed();
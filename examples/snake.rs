@@ -1,57 +1,51 @@
-use std::thread::sleep;
-use std::time::Duration;
+use core::ops::ControlFlow;
 
-use bracket_color::hsv::HSV;
-use esp_remotecontrolled_led_matrix_client::sync::Client;
+use esp_remotecontrolled_led_matrix_client::backoff::Backoff;
+use esp_remotecontrolled_led_matrix_client::sync::{FrameLoop, ReconnectingClient};
+use esp_remotecontrolled_led_matrix_client::{Error, Rgb};
 use snake_logic::{Point, get_next_point};
 
-const RUN_SLEEP: Duration = Duration::from_millis(200);
-const DECAY_SLEEP: Duration = Duration::from_millis(100);
+const RUN_FPS: f32 = 5.0;
+const DECAY_FPS: f32 = 10.0;
 
 fn main() {
     let addr = std::env::var("ADDR");
     let addr = addr.as_deref().unwrap_or("espPixelmatrix:1337");
 
-    loop {
-        match Client::connect(addr) {
-            Ok(client) => {
-                println!(
-                    "size {}x{} = {} pixels",
-                    client.width(),
-                    client.height(),
-                    client.total_pixels()
-                );
-                if let Err(err) = snake(&client) {
-                    eprintln!("ERROR: {err}");
-                }
-            }
-            Err(err) => {
-                eprintln!("CONNECT ERROR: {err}");
-                sleep(Duration::from_millis(500));
-            }
-        }
-    }
+    let mut client =
+        ReconnectingClient::connect(addr, Backoff::default()).expect("connection error");
+    println!(
+        "size {}x{} = {} pixels",
+        client.width(),
+        client.height(),
+        client.total_pixels()
+    );
+    snake(&mut client).expect("snake failed");
 }
 
-fn do_death(client: &Client, snake: &[Point], food: Point) -> std::io::Result<()> {
+fn do_death(client: &mut ReconnectingClient, snake: &[Point], food: Point) -> Result<(), Error> {
     println!(
         "snake length {:3} died at {:3} {:3}",
         snake.len(),
         snake.first().unwrap().x,
         snake.first().unwrap().y,
     );
-    for point in snake {
-        client.pixel(point.x, point.y, 0, 0, 0)?;
-        client.flush()?;
-        sleep(DECAY_SLEEP);
-    }
-    client.pixel(food.x, food.y, 0, 0, 0)?;
+    let mut points = snake.iter();
+    FrameLoop::new(DECAY_FPS).run(client, |client, _frame| {
+        let Some(point) = points.next() else {
+            return Ok(ControlFlow::Break(()));
+        };
+        client.pixel(point.x, point.y, Rgb::BLACK)?;
+        Ok(ControlFlow::Continue(()))
+    })?;
+    client.pixel(food.x, food.y, Rgb::BLACK)?;
     Ok(())
 }
 
-fn snake(client: &Client) -> std::io::Result<()> {
+fn snake(client: &mut ReconnectingClient) -> Result<(), Error> {
     let width = client.width();
     let height = client.height();
+    let mut frame_loop = FrameLoop::new(RUN_FPS);
     loop {
         let mut food = Point::random(width, height);
         let mut hue = rand::random::<f32>() % 360.0;
@@ -69,18 +63,18 @@ fn snake(client: &Client) -> std::io::Result<()> {
             vec![start, end]
         };
 
-        loop {
+        frame_loop.run(client, |client, _frame| {
             let next_point = if let Some(point) = get_next_point(width, height, &snake, food) {
                 // Hits itself
                 if snake.contains(&point) {
                     do_death(client, &snake, food)?;
-                    break;
+                    return Ok(ControlFlow::Break(()));
                 }
 
                 point
             } else {
                 do_death(client, &snake, food)?;
-                break;
+                return Ok(ControlFlow::Break(()));
             };
 
             #[cfg(debug_assertions)]
@@ -97,37 +91,15 @@ fn snake(client: &Client) -> std::io::Result<()> {
                 food = Point::random(width, height);
             } else {
                 let last = snake.pop().unwrap();
-                client.pixel(last.x, last.y, 0, 0, 0)?;
+                client.pixel(last.x, last.y, Rgb::BLACK)?;
             }
 
             hue = (hue + 5.0) % 360.0;
-            {
-                let (red, green, blue) = hue_to_rgb(hue);
-                client.pixel(next_point.x, next_point.y, red, green, blue)?;
-            }
+            client.pixel(next_point.x, next_point.y, Rgb::from_hsv(hue, 1.0, 1.0))?;
             snake.insert(0, next_point);
 
-            {
-                let (red, green, blue) = hue_to_rgb((hue + 180.0) % 360.0);
-                client.pixel(food.x, food.y, red, green, blue)?;
-            }
-
-            client.flush()?;
-            sleep(RUN_SLEEP);
-        }
+            client.pixel(food.x, food.y, Rgb::from_hsv(hue + 180.0, 1.0, 1.0))?;
+            Ok(ControlFlow::Continue(()))
+        })?;
     }
 }
-
-/// Converts from f32 Hue to u8 rgb values
-/// * `hue` - Hue from 0.0 to 360.0
-#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-fn hue_to_rgb(hue: f32) -> (u8, u8, u8) {
-    let hsv = HSV::from_f32(hue / 360.0, 1.0, 1.0);
-    let rgb = hsv.to_rgb();
-
-    let red = (rgb.r * 255.0) as u8;
-    let green = (rgb.g * 255.0) as u8;
-    let blue = (rgb.b * 255.0) as u8;
-
-    (red, green, blue)
-}
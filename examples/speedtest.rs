@@ -1,70 +1,60 @@
-use std::thread::sleep;
-use std::time::{Duration, Instant};
+use core::ops::ControlFlow;
 
-use esp_remotecontrolled_led_matrix_client::sync::Client;
+use esp_remotecontrolled_led_matrix_client::backoff::Backoff;
+use esp_remotecontrolled_led_matrix_client::sync::{FrameLoop, ReconnectingClient};
+use esp_remotecontrolled_led_matrix_client::{Error, Rgb};
 
 fn main() {
     let addr = std::env::var("ADDR");
     let addr = addr.as_deref().unwrap_or("espPixelmatrix:1337");
 
-    loop {
-        match Client::connect(addr) {
-            Ok(client) => {
-                println!(
-                    "{} size {}x{} = {} pixels",
-                    addr,
-                    client.width(),
-                    client.height(),
-                    client.total_pixels()
-                );
-                if let Err(err) = speedtest(&client) {
-                    eprintln!("ERROR: {err}");
-                }
-            }
-            Err(err) => {
-                eprintln!("CONNECT ERROR: {err}");
-                sleep(Duration::from_millis(500));
-            }
-        }
-    }
+    let mut client =
+        ReconnectingClient::connect(addr, Backoff::default()).expect("connection error");
+    println!(
+        "{} size {}x{} = {} pixels",
+        addr,
+        client.width(),
+        client.height(),
+        client.total_pixels()
+    );
+    speedtest(&mut client).expect("speedtest failed");
 }
 
-#[expect(clippy::cast_precision_loss)]
-fn speedtest(client: &Client) -> std::io::Result<()> {
-    let total_pixels = client.total_pixels() as usize;
-    let start = Instant::now();
-    let mut pixel_wrote: usize = 0;
-
-    loop {
-        let write = Instant::now();
-
-        for y in 0..client.height() {
-            for x in 0..client.width() {
-                let red = rand::random::<u8>();
-                let green = rand::random::<u8>() / 2;
-                let blue = rand::random::<u8>() / 3;
-
-                client.pixel(x, y, red, green, blue)?;
-                pixel_wrote = pixel_wrote.overflowing_add(1).0;
+fn speedtest(client: &mut ReconnectingClient) -> Result<(), Error> {
+    let mut frame_loop = FrameLoop::new(f32::INFINITY);
+    while frame_loop
+        .frame(client, |client, _frame| {
+            for y in 0..client.height() {
+                for x in 0..client.width() {
+                    let red = rand::random::<u8>();
+                    let green = rand::random::<u8>() / 2;
+                    let blue = rand::random::<u8>() / 3;
+
+                    client.pixel(x, y, Rgb::new(red, green, blue))?;
+                }
             }
-        }
-
-        client.flush()?;
-
-        let took = write.elapsed();
-
-        let pixel_per_second = (pixel_wrote as f64) / start.elapsed().as_secs_f64();
-        let screens_per_second =
-            (pixel_wrote / total_pixels) as f64 / start.elapsed().as_secs_f64();
-        let kb_per_second = pixel_per_second * 6.0 / 1024.0;
-
+            Ok(ControlFlow::Continue(()))
+        })?
+        .is_continue()
+    {
+        let stats = frame_loop.stats();
+        let fps = stats.fps().unwrap_or_default();
+        let bytes_per_frame = stats.bytes_per_frame().unwrap_or_default();
+        let millis = |percentile| {
+            stats
+                .flush_latency(percentile)
+                .unwrap_or_default()
+                .as_secs_f64()
+                * 1000.0
+        };
         println!(
-            "{:6.1}s since start; took {:9.2} ms for a screen; Average:{:12.1} pixels / second {:9.3} screens / second {:9.3} kB/s",
-            start.elapsed().as_secs_f64(),
-            took.as_secs_f64() * 1000.0,
-            pixel_per_second,
-            screens_per_second,
-            kb_per_second,
+            "{:6} screens; flush took {:7.2} ms median {:7.2} ms p99; Average:{:9.3} screens / second {:9.3} kB/s",
+            stats.frames(),
+            millis(50.0),
+            millis(99.0),
+            fps,
+            fps * bytes_per_frame / 1024.0,
         );
     }
+    Ok(())
 }
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use esp_remotecontrolled_led_matrix_client::async_tokio::Client;
+use esp_remotecontrolled_led_matrix_client::{Error, Rgb};
 use rand::Rng as _;
 use tokio::task;
 use tokio::time::sleep;
@@ -27,11 +28,11 @@ async fn main() {
     }
 }
 
-async fn spawn_star(client: Client) -> std::io::Result<()> {
-    async fn fade_away(client: Client, x: u8, y: u8) -> std::io::Result<()> {
+async fn spawn_star(client: Client) -> Result<(), Error> {
+    async fn fade_away(client: Client, x: u8, y: u8) -> Result<(), Error> {
         for bri in [100_u8, 0] {
             sleep(Duration::from_millis(150)).await;
-            client.pixel(x, y, bri, bri, bri).await?;
+            client.pixel(x, y, Rgb::new(bri, bri, bri)).await?;
             client.flush().await?;
         }
         Ok(())
@@ -45,7 +46,7 @@ async fn spawn_star(client: Client) -> std::io::Result<()> {
     };
     println!("star {x:3} {y:3}");
 
-    client.pixel(x, y, 255, 255, 255).await?;
+    client.pixel(x, y, Rgb::WHITE).await?;
     client.flush().await?;
 
     task::spawn(async move {
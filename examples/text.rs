@@ -4,16 +4,17 @@ use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::mono_font::ascii::FONT_5X7;
 use embedded_graphics::pixelcolor::{Rgb888, RgbColor as _};
 use embedded_graphics::text::Text;
-use esp_remotecontrolled_led_matrix_client::sync::Client;
+use esp_remotecontrolled_led_matrix_client::async_tokio::Client;
 
-fn main() {
+#[tokio::main]
+async fn main() {
     let addr = std::env::var("ADDR");
     let addr = addr.as_deref().unwrap_or("espPixelmatrix:1337");
 
     let text = std::env::var("TEXT");
     let text = text.as_deref().unwrap_or("hey!");
 
-    let mut client = Client::connect(addr).expect("connection error");
+    let client = Client::connect(addr).await.expect("connection error");
 
     println!(
         "{} size {}x{} = {} pixels",
@@ -23,12 +24,13 @@ fn main() {
         client.total_pixels()
     );
 
-    client.fill(0, 0, 0).unwrap();
+    let mut framebuffer = client.framebuffer();
     Text::new(
         text,
         Point::new(0, 6),
         MonoTextStyle::new(&FONT_5X7, Rgb888::MAGENTA),
     )
-    .draw(&mut client)
+    .draw(&mut framebuffer)
     .unwrap();
+    framebuffer.flush_to(&client).await.unwrap();
 }
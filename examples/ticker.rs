@@ -0,0 +1,43 @@
+use core::ops::ControlFlow;
+
+use embedded_graphics::mono_font::ascii::FONT_5X7;
+use esp_remotecontrolled_led_matrix_client::Rgb;
+use esp_remotecontrolled_led_matrix_client::async_tokio::Client;
+use esp_remotecontrolled_led_matrix_client::ticker::Ticker;
+
+#[tokio::main]
+async fn main() {
+    let addr = std::env::var("ADDR");
+    let addr = addr.as_deref().unwrap_or("espPixelmatrix:1337");
+
+    let text = std::env::var("TEXT");
+    let text = text.as_deref().unwrap_or("Scrolling through the matrix");
+
+    let client = Client::connect(addr).await.expect("connection error");
+
+    println!(
+        "{} size {}x{} = {} pixels",
+        addr,
+        client.width(),
+        client.height(),
+        client.total_pixels()
+    );
+
+    let mut ticker = Ticker::new(&FONT_5X7)
+        .segment("+++ ", Rgb::RED)
+        .segment(text, Rgb::new(255, 0, 255))
+        .segment(" +++", Rgb::RED)
+        .speed(15.0);
+    client
+        .run_ticker(&mut ticker, 30.0, |ticker, frame| {
+            // Hold still for a moment every 10 seconds
+            if frame.elapsed.as_secs() % 10 == 9 {
+                ticker.pause();
+            } else {
+                ticker.resume();
+            }
+            ControlFlow::Continue(())
+        })
+        .await
+        .unwrap();
+}
@@ -0,0 +1,462 @@
+//! Decode animated GIF, APNG and WebP images scaled to the display.
+//!
+//! The clients play them with `play_animation`.
+
+use core::time::Duration;
+use std::io::Cursor;
+use std::path::Path;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder as _, DynamicImage, ImageFormat};
+
+use crate::correction::Correction;
+use crate::error::Error;
+use crate::imaging::ImageOptions;
+
+/// Frames with a shorter delay are shown for [`DEFAULT_DELAY`] like browsers do.
+const MIN_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// How often the animation is played.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoopCount {
+    #[default]
+    Infinite,
+    /// Amount of times the whole animation is played. The last frame stays afterwards.
+    Finite(u32),
+}
+
+/// One frame of an [`Animation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationFrame {
+    /// How long the frame is shown.
+    pub delay: Duration,
+    /// Every pixel of the display, row by row starting in the top left.
+    pub pixels: Vec<[u8; 3]>,
+}
+
+/// Frames of an animated image scaled to the display size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    width: u8,
+    height: u8,
+    frames: Vec<AnimationFrame>,
+    loop_count: LoopCount,
+}
+
+impl Animation {
+    /// Load an animated image file and scale it to the display size.
+    ///
+    /// Images without animation result in a single frame.
+    ///
+    /// # Errors
+    /// Errors when the file could not be read or decoded
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        display_width: u8,
+        display_height: u8,
+        options: ImageOptions,
+    ) -> Result<Self, Error> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes, display_width, display_height, options)
+    }
+
+    /// Decode an animated image and scale it to the display size.
+    ///
+    /// The format is detected from the content.
+    /// Images without animation result in a single frame.
+    ///
+    /// # Errors
+    /// Errors when the image could not be decoded
+    pub fn from_bytes(
+        bytes: &[u8],
+        display_width: u8,
+        display_height: u8,
+        options: ImageOptions,
+    ) -> Result<Self, Error> {
+        Self::decode(bytes, display_width, display_height, options, None)
+    }
+
+    /// Like [`from_bytes`](Self::from_bytes) for a display which applies the given correction to the colors.
+    ///
+    /// The [dithering](crate::imaging::Dither) then mixes the levels the LEDs actually show after the correction,
+    /// see [`ImageOptions::place_corrected`].
+    ///
+    /// # Errors
+    /// Errors when the image could not be decoded
+    pub fn from_bytes_corrected(
+        bytes: &[u8],
+        display_width: u8,
+        display_height: u8,
+        options: ImageOptions,
+        correction: &Correction,
+    ) -> Result<Self, Error> {
+        Self::decode(
+            bytes,
+            display_width,
+            display_height,
+            options,
+            Some(correction),
+        )
+    }
+
+    pub(crate) fn decode(
+        bytes: &[u8],
+        display_width: u8,
+        display_height: u8,
+        options: ImageOptions,
+        correction: Option<&Correction>,
+    ) -> Result<Self, Error> {
+        let format = image::guess_format(bytes)?;
+        let frames = match format {
+            ImageFormat::Gif => GifDecoder::new(Cursor::new(bytes))?
+                .into_frames()
+                .collect_frames()?,
+            ImageFormat::Png => PngDecoder::new(Cursor::new(bytes))?
+                .apng()?
+                .into_frames()
+                .collect_frames()?,
+            ImageFormat::WebP => {
+                let decoder = WebPDecoder::new(Cursor::new(bytes))?;
+                if decoder.has_animation() {
+                    decoder.into_frames().collect_frames()?
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        let place = |image: &DynamicImage, delay: Duration| {
+            let mut pixels = vec![[0; 3]; usize::from(display_width) * usize::from(display_height)];
+            if let Some(placed) =
+                options.place_for(image, display_width, display_height, correction)
+            {
+                let rows = placed.colors.chunks_exact(placed.width.into());
+                for (y, row) in (placed.y..).zip(rows) {
+                    let start = usize::from(y) * usize::from(display_width) + usize::from(placed.x);
+                    for (pixel, color) in pixels[start..].iter_mut().zip(row) {
+                        *pixel = color.to_array();
+                    }
+                }
+            }
+            let delay = if delay < MIN_DELAY {
+                DEFAULT_DELAY
+            } else {
+                delay
+            };
+            AnimationFrame { delay, pixels }
+        };
+
+        let (frames, loop_count) = if frames.is_empty() {
+            let image = image::load_from_memory_with_format(bytes, format)?;
+            (vec![place(&image, DEFAULT_DELAY)], LoopCount::Finite(1))
+        } else {
+            let frames = frames
+                .into_iter()
+                .map(|frame| {
+                    let delay = Duration::from(frame.delay());
+                    place(&DynamicImage::ImageRgba8(frame.into_buffer()), delay)
+                })
+                .collect();
+            (frames, loop_count(format, bytes))
+        };
+        Ok(Self {
+            width: display_width,
+            height: display_height,
+            frames,
+            loop_count,
+        })
+    }
+
+    /// Play the animation a different amount of times than the file specifies.
+    #[must_use]
+    pub const fn with_loop_count(mut self, loop_count: LoopCount) -> Self {
+        self.loop_count = loop_count;
+        self
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.height
+    }
+
+    #[must_use]
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    #[must_use]
+    pub const fn loop_count(&self) -> LoopCount {
+        self.loop_count
+    }
+
+    /// Duration of playing every frame once.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.delay).sum()
+    }
+
+    /// Index of the frame to show after the given time of playing and how long it is shown from then on.
+    ///
+    /// Returns `None` when the animation is finished.
+    /// Players looking this up on every frame skip frames when they fall behind.
+    #[must_use]
+    pub fn frame_at(&self, elapsed: Duration) -> Option<(usize, Duration)> {
+        let duration = self.duration();
+        if duration.is_zero() {
+            return None;
+        }
+        let plays = elapsed.as_nanos() / duration.as_nanos();
+        if let LoopCount::Finite(loops) = self.loop_count {
+            if plays >= u128::from(loops) {
+                return None;
+            }
+        }
+        let mut remaining = Duration::from_nanos(
+            u64::try_from(elapsed.as_nanos() % duration.as_nanos()).unwrap_or_default(),
+        );
+        for (index, frame) in self.frames.iter().enumerate() {
+            if remaining < frame.delay {
+                return Some((index, frame.delay.saturating_sub(remaining)));
+            }
+            remaining -= frame.delay;
+        }
+        None
+    }
+}
+
+/// Amount of plays stored in the file. Without it the animation is played once.
+fn loop_count(format: ImageFormat, bytes: &[u8]) -> LoopCount {
+    let stored = match format {
+        ImageFormat::Gif => gif_repeat(bytes).map(|repeat| match repeat {
+            0 => 0,
+            repeat => u32::from(repeat) + 1,
+        }),
+        ImageFormat::Png => apng_plays(bytes),
+        ImageFormat::WebP => webp_loops(bytes).map(u32::from),
+        _ => None,
+    };
+    match stored {
+        // Every format uses zero for infinite
+        Some(0) => LoopCount::Infinite,
+        Some(plays) => LoopCount::Finite(plays),
+        None => LoopCount::Finite(1),
+    }
+}
+
+/// Repetitions of the NETSCAPE2.0 application extension. 0 repeats forever.
+fn gif_repeat(bytes: &[u8]) -> Option<u16> {
+    // Header and logical screen descriptor, optionally followed by the global color table
+    let flags = *bytes.get(10)?;
+    let global_colors = if flags & 0x80 == 0 {
+        0
+    } else {
+        3 << ((flags & 0x07) + 1)
+    };
+    let mut rest = bytes.get(13 + global_colors..)?;
+    // Extensions until the first image descriptor
+    while let [0x21, label, blocks @ ..] = rest {
+        let (mut block, mut after) = sub_block(blocks)?;
+        if *label == 0xFF && block == b"NETSCAPE2.0" {
+            return match *sub_block(after)?.0 {
+                [1, low, high] => Some(u16::from_le_bytes([low, high])),
+                _ => None,
+            };
+        }
+        while !block.is_empty() {
+            (block, after) = sub_block(after)?;
+        }
+        rest = after;
+    }
+    None
+}
+
+/// Data of the GIF sub-block at the start and everything after it. The terminator has no data.
+fn sub_block(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (length, rest) = bytes.split_first()?;
+    rest.split_at_checked(usize::from(*length))
+}
+
+/// Plays of the `acTL` chunk. 0 plays forever.
+fn apng_plays(bytes: &[u8]) -> Option<u32> {
+    let mut rest = bytes.get(8..)?;
+    while let Some((header, data)) = rest.split_at_checked(8) {
+        let length = usize::try_from(u32::from_be_bytes(header[..4].try_into().ok()?)).ok()?;
+        match &header[4..] {
+            b"acTL" => return Some(u32::from_be_bytes(data.get(4..8)?.try_into().ok()?)),
+            b"IDAT" | b"IEND" => return None,
+            // Skip the data and the checksum
+            _ => rest = data.get(length.checked_add(4)?..)?,
+        }
+    }
+    None
+}
+
+/// Loop count of the `ANIM` chunk. 0 loops forever.
+fn webp_loops(bytes: &[u8]) -> Option<u16> {
+    let mut rest = bytes.get(12..)?;
+    while let Some((header, data)) = rest.split_at_checked(8) {
+        let length = usize::try_from(u32::from_le_bytes(header[4..].try_into().ok()?)).ok()?;
+        if &header[..4] == b"ANIM" {
+            return Some(u16::from_le_bytes(data.get(4..6)?.try_into().ok()?));
+        }
+        // Chunks are padded to an even length
+        rest = data.get(length.checked_add(length % 2)?..)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use image::codecs::gif::{GifEncoder, Repeat};
+    use image::{Delay, Frame, Rgba, RgbaImage};
+
+    use super::*;
+    use crate::imaging::Dither;
+
+    fn gif(repeat: Option<Repeat>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut bytes);
+            if let Some(repeat) = repeat {
+                encoder.set_repeat(repeat).unwrap();
+            }
+            let frames = [([255, 0, 0, 255], 50), ([0, 0, 255, 255], 200)].map(|(color, delay)| {
+                Frame::from_parts(
+                    RgbaImage::from_pixel(2, 2, Rgba(color)),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(delay, 1),
+                )
+            });
+            encoder.encode_frames(frames).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn decodes_gif() {
+        let animation =
+            Animation::from_bytes(&gif(Some(Repeat::Finite(2))), 4, 2, ImageOptions::new())
+                .unwrap();
+        assert_eq!(animation.frames().len(), 2);
+        assert_eq!(animation.loop_count(), LoopCount::Finite(3));
+        let first = &animation.frames()[0];
+        assert_eq!(first.delay, Duration::from_millis(50));
+        assert_eq!(
+            first.pixels,
+            [
+                [0, 0, 0],
+                [255, 0, 0],
+                [255, 0, 0],
+                [0, 0, 0],
+                [0, 0, 0],
+                [255, 0, 0],
+                [255, 0, 0],
+                [0, 0, 0],
+            ]
+        );
+        assert_eq!(animation.frames()[1].pixels[1], [0, 0, 255]);
+    }
+
+    #[test]
+    fn dithering_targets_corrected_levels() {
+        // Full red ends up between two levels of the LEDs
+        let correction = Correction::new().brightness(0.1);
+        let decode = |dither| {
+            let options = ImageOptions::new().dither(dither);
+            Animation::from_bytes_corrected(&gif(None), 8, 8, options, &correction).unwrap()
+        };
+        let plain = decode(Dither::None);
+        let dithered = decode(Dither::Bayer);
+        assert!(plain.frames()[0].pixels.iter().all(|pixel| pixel[0] == 255));
+        assert_ne!(plain.frames()[0].pixels, dithered.frames()[0].pixels);
+    }
+
+    #[test]
+    fn gif_loop_counts() {
+        let decode = |repeat| {
+            Animation::from_bytes(&gif(repeat), 2, 2, ImageOptions::new())
+                .unwrap()
+                .loop_count()
+        };
+        assert_eq!(decode(None), LoopCount::Finite(1));
+        assert_eq!(decode(Some(Repeat::Infinite)), LoopCount::Infinite);
+        assert_eq!(decode(Some(Repeat::Finite(1))), LoopCount::Finite(2));
+    }
+
+    #[test]
+    fn gif_repeat_only_from_application_extension() {
+        let netscape = |label| {
+            let mut gif = b"GIF89a".to_vec();
+            // Logical screen descriptor with a global color table of two colors
+            gif.extend([1, 0, 1, 0, 0x80, 0, 0]);
+            gif.extend([0; 6]);
+            gif.extend([0x21, label, 11]);
+            gif.extend(b"NETSCAPE2.0");
+            gif.extend([3, 1, 5, 0, 0]);
+            gif.extend([0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0]);
+            gif
+        };
+        assert_eq!(gif_repeat(&netscape(0xFF)), Some(5));
+        // Comment extension
+        assert_eq!(gif_repeat(&netscape(0xFE)), None);
+
+        // Behind the first image
+        let mut gif = netscape(0xFE);
+        gif.extend([2, 3]);
+        gif.extend(netscape(0xFF));
+        assert_eq!(gif_repeat(&gif), None);
+    }
+
+    #[test]
+    fn apng_and_webp_loop_counts() {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend([0, 0, 0, 13]);
+        png.extend(b"IHDR");
+        png.extend([0; 13 + 4]);
+        png.extend([0, 0, 0, 8]);
+        png.extend(b"acTL");
+        png.extend([0, 0, 0, 2, 0, 0, 0, 5]);
+        assert_eq!(apng_plays(&png), Some(5));
+
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend(b"VP8X");
+        webp.extend([10, 0, 0, 0]);
+        webp.extend([0; 10]);
+        webp.extend(b"ANIM");
+        webp.extend([6, 0, 0, 0]);
+        webp.extend([0, 0, 0, 0, 4, 0]);
+        assert_eq!(webp_loops(&webp), Some(4));
+        assert_eq!(loop_count(ImageFormat::WebP, &webp), LoopCount::Finite(4));
+    }
+
+    #[test]
+    fn frame_at() {
+        let animation = Animation::from_bytes(&gif(None), 2, 2, ImageOptions::new()).unwrap();
+        assert_eq!(animation.duration(), Duration::from_millis(250));
+        assert_eq!(
+            animation.frame_at(Duration::ZERO),
+            Some((0, Duration::from_millis(50)))
+        );
+        assert_eq!(
+            animation.frame_at(Duration::from_millis(60)),
+            Some((1, Duration::from_millis(190)))
+        );
+        assert_eq!(animation.frame_at(Duration::from_millis(250)), None);
+
+        let animation = animation.with_loop_count(LoopCount::Infinite);
+        assert_eq!(
+            animation.frame_at(Duration::from_secs(100) + Duration::from_millis(10)),
+            Some((0, Duration::from_millis(40)))
+        );
+    }
+}
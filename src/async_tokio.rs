@@ -1,45 +1,61 @@
+use core::time::Duration;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _, BufStream};
+use tokio::io::{AsyncWriteExt as _, BufStream};
 use tokio::net::{TcpStream, ToSocketAddrs};
 use tokio::sync::Mutex;
 
-use crate::Command;
+pub use self::builder::ClientBuilder;
+use self::builder::with_timeout;
+pub use self::canvas::Canvas;
+pub use self::double_buffer::DoubleBuffer;
+pub use self::frame_loop::{FrameLoop, FrameTarget};
+pub use self::reconnecting::ReconnectingClient;
+pub use self::tiled::TiledDisplay;
+pub use self::transformed::TransformedClient;
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::pipeline::Pipeline;
+use crate::protocol::{Bounds, Message};
+
+mod builder;
+mod canvas;
+mod double_buffer;
+mod frame_loop;
+mod reconnecting;
+mod tiled;
+mod transformed;
 
 #[derive(Clone)]
 pub struct Client {
-    stream: Arc<Mutex<BufStream<TcpStream>>>,
+    /// `None` once a write failed as it might have left a partial command on the connection.
+    stream: Arc<Mutex<Option<BufStream<TcpStream>>>>,
     width: u8,
     height: u8,
+    write_timeout: Option<Duration>,
+    bytes_sent: Arc<AtomicU64>,
+    pipeline: Pipeline,
 }
 
 impl Client {
+    /// Configure timeouts and bounds handling before connecting to the server
+    pub const fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
     /// Connect to the server
     ///
+    /// See [`ClientBuilder`] for timeouts.
+    ///
     /// # Errors
     /// Errors when the connection could not be established.
-    pub async fn connect<Address>(address: Address) -> std::io::Result<Self>
+    pub async fn connect<Address>(address: Address) -> Result<Self, Error>
     where
         Address: ToSocketAddrs + Send,
     {
-        let stream = TcpStream::connect(address).await?;
-        let mut stream = BufStream::new(stream);
-
-        let mut protocol_version = [0; 1];
-        stream.read_exact(&mut protocol_version).await?;
-        if protocol_version[0] != 1 {
-            return Err(std::io::Error::other("Protocol version is not 1"));
-        }
-
-        let mut buf = [0; 2];
-        stream.read_exact(&mut buf).await?;
-        let [width, height] = buf;
-
-        Ok(Self {
-            stream: Arc::new(Mutex::new(stream)),
-            width,
-            height,
-        })
+        ClientBuilder::new().connect(address).await
     }
 
     #[must_use]
@@ -57,107 +73,395 @@ impl Client {
         (self.width as u16) * (self.height as u16)
     }
 
+    /// How drawing commands which do not fit on the display are handled.
+    #[must_use]
+    pub const fn bounds(&self) -> Bounds {
+        self.pipeline.bounds()
+    }
+
+    /// Estimated current drawn by the display when a [`PowerBudget`](crate::power::PowerBudget) is configured.
+    #[must_use]
+    pub fn estimated_milliamps(&self) -> Option<f32> {
+        self.pipeline.estimated_milliamps()
+    }
+
+    /// Amount of bytes written to the connection so far, shared between clones of this client.
+    #[must_use]
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Create a [`Framebuffer`] with the size of the display.
+    #[must_use]
+    pub fn framebuffer(&self) -> Framebuffer {
+        Framebuffer::new(self.width, self.height)
+    }
+
     /// Flushes the internal buffer and sends everything to the server
     ///
     /// # Errors
-    /// Errors when the command could not be sent
-    pub async fn flush(&self) -> std::io::Result<()> {
-        self.stream.lock().await.flush().await
+    /// Errors when the command could not be sent.
+    /// After a failed write every further command fails, [reconnect](ReconnectingClient) to continue.
+    pub async fn flush(&self) -> Result<(), Error> {
+        let mut stream = self.stream.lock().await;
+        // Taken out while writing so a failed or cancelled write leaves the connection closed.
+        // It might have sent a partial command which would make the server misread everything afterwards.
+        let mut connection = stream.take().ok_or_else(crate::error::broken_connection)?;
+        with_timeout(self.write_timeout, "write", connection.flush()).await?;
+        *stream = Some(connection);
+        drop(stream);
+        Ok(())
     }
 
-    /// Set one pixel of the matrix to the given color.
+    /// Send the difference between the last presented and the current content of the [`Framebuffer`] and flush.
+    ///
+    /// The whole difference is written at once, so the new frame appears as a whole as far as the protocol allows.
+    ///
+    /// # Errors
+    /// Errors when the framebuffer size does not match the display or the commands could not be sent
+    pub async fn present(&self, framebuffer: &mut Framebuffer) -> Result<(), Error> {
+        if framebuffer.width() != self.width || framebuffer.height() != self.height {
+            return Err(Error::SizeMismatch {
+                display: (self.width, self.height),
+                content: (framebuffer.width(), framebuffer.height()),
+            });
+        }
+        self.send_all(&framebuffer.diff()).await?;
+        self.flush().await?;
+        framebuffer.mark_presented();
+        Ok(())
+    }
+
+    /// Send what changed in the [`Region`](crate::widgets::Region) since its last update.
     /// Do not forget to also run [`flush`](Self::flush) afterwards.
     ///
     /// # Errors
-    /// Errors when the data could not be written to the send buffer
-    pub async fn pixel(&self, x: u8, y: u8, red: u8, green: u8, blue: u8) -> std::io::Result<()> {
-        self.stream
-            .lock()
-            .await
-            .write_all(&[Command::Pixel as u8, x, y, red, green, blue])
+    /// Errors when the region does not fit on the display or the commands could not be sent
+    pub async fn update_widget<W: crate::widgets::Widget>(
+        &self,
+        region: &mut crate::widgets::Region<W>,
+    ) -> Result<(), Error> {
+        self.send_all(&region.changes()).await?;
+        region.mark_presented();
+        Ok(())
+    }
+
+    /// Blend the layers of the [`Compositor`](crate::compositor::Compositor) and send the whole result
+    /// via [`contiguous`](Self::contiguous).
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the compositor size does not match the display or the command could not be sent
+    pub async fn display_composition(
+        &self,
+        compositor: &crate::compositor::Compositor,
+    ) -> Result<(), Error> {
+        if compositor.width() != self.width || compositor.height() != self.height {
+            return Err(Error::SizeMismatch {
+                display: (self.width, self.height),
+                content: (compositor.width(), compositor.height()),
+            });
+        }
+        self.contiguous(0, 0, self.width, self.height, &compositor.compose())
             .await
     }
 
+    /// Send a single [`Message`] to the server.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// Messages which do not fit on the display are handled according to the [`Bounds`](crate::protocol::Bounds) of the [`ClientBuilder`].
+    /// Afterwards the configured [`Correction`](crate::correction::Correction) and [`PowerBudget`](crate::power::PowerBudget) are applied.
+    ///
+    /// # Errors
+    /// Errors when the message is invalid for the display (see [`Message::validate`])
+    /// or the data could not be written to the send buffer
+    pub async fn send(&self, message: &Message) -> Result<(), Error> {
+        self.send_all(core::slice::from_ref(message)).await
+    }
+
+    /// Send several [`Message`]s with a single write to the connection.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// Each message is handled like in [`send`](Self::send).
+    /// The [`PowerBudget`](crate::power::PowerBudget) dims all of them by the same factor, so a frame keeps its look.
+    /// Nothing is written when one of the messages is invalid.
+    ///
+    /// # Errors
+    /// Errors when one of the messages is invalid for the display (see [`Message::validate`])
+    /// or the data could not be written to the send buffer
+    pub async fn send_all(&self, messages: &[Message]) -> Result<(), Error> {
+        // Prepared while locked so the power limit follows the order of the writes
+        let mut stream = self.stream.lock().await;
+        let prepared = self.pipeline.prepare(messages)?;
+        if prepared.bytes.is_empty() {
+            return Ok(());
+        }
+        // Taken out while writing like in flush
+        let mut connection = stream.take().ok_or_else(crate::error::broken_connection)?;
+        with_timeout(
+            self.write_timeout,
+            "write",
+            connection.write_all(&prepared.bytes),
+        )
+        .await?;
+        *stream = Some(connection);
+        self.bytes_sent
+            .fetch_add(prepared.bytes.len() as u64, Ordering::Relaxed);
+        self.pipeline.commit(prepared);
+        drop(stream);
+        Ok(())
+    }
+
+    /// Set one pixel of the matrix to the given color.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the pixel is not on the display and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the data could not be written to the send buffer
+    pub async fn pixel(&self, x: u8, y: u8, color: Rgb) -> Result<(), Error> {
+        self.send(&Message::Pixel {
+            x,
+            y,
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+        .await
+    }
+
     /// Fill the whole matrix with one color.
     /// Do not forget to also run [`flush`](Self::flush) afterwards.
     ///
     /// # Errors
     /// Errors when the command could not be sent
-    pub async fn fill(&self, red: u8, green: u8, blue: u8) -> std::io::Result<()> {
-        self.stream
-            .lock()
-            .await
-            .write_all(&[Command::Fill as u8, red, green, blue])
-            .await
+    pub async fn fill(&self, color: Rgb) -> Result<(), Error> {
+        self.send(&Message::Fill {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+        .await
     }
 
-    #[expect(clippy::too_many_arguments)]
     /// Fill the given rectangular area with one color.
     /// Do not forget to also run [`flush`](Self::flush) afterwards.
     ///
     /// # Errors
-    /// Errors when the command could not be sent
+    /// Errors when the area does not fit on the display and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the command could not be sent
     pub async fn rectangle(
         &self,
         x: u8,
         y: u8,
         width: u8,
         height: u8,
-        red: u8,
-        green: u8,
-        blue: u8,
-    ) -> std::io::Result<()> {
-        self.stream
-            .lock()
-            .await
-            .write_all(&[
-                Command::Rectangle as u8,
-                x,
-                y,
-                width,
-                height,
-                red,
-                green,
-                blue,
-            ])
-            .await
+        color: Rgb,
+    ) -> Result<(), Error> {
+        self.send(&Message::Rectangle {
+            x,
+            y,
+            width,
+            height,
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+        .await
     }
 
     /// Send an area full of colors.
     ///
     /// The area begins in the top left at x/y and moves first on the x axis, then on the y axis.
-    /// The colors are given in R G B order.
     ///
     /// Do not forget to also run [`flush`](Self::flush) afterwards.
     ///
     /// # Errors
-    /// Errors when the command could not be sent
+    /// Errors when the amount of colors does not match the area,
+    /// the area does not fit on the display and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the command could not be sent
     pub async fn contiguous(
         &self,
         x: u8,
         y: u8,
         width: u8,
         height: u8,
-        colors: &[u8],
-    ) -> std::io::Result<()> {
-        let too_wide = x.checked_add(width).is_none_or(|width| width > self.width);
-        let too_high = y
-            .checked_add(height)
-            .is_none_or(|height| height > self.height);
-        if too_wide || too_high {
-            return Err(std::io::Error::other("area too big for display"));
-        }
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        self.send(&Message::Contiguous {
+            x,
+            y,
+            width,
+            height,
+            colors: colors.iter().flat_map(|color| color.to_array()).collect(),
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl Client {
+    /// Scroll the ticker at the given frame rate until it is [finished](crate::ticker::Ticker::is_finished)
+    /// or `control` returns [`ControlFlow::Break`](core::ops::ControlFlow::Break).
+    ///
+    /// `control` is called before every frame and can for example pause the ticker or change its text.
+    /// Only the difference to the previous frame is sent.
+    ///
+    /// # Errors
+    /// Errors when the commands could not be sent
+    pub async fn run_ticker(
+        &self,
+        ticker: &mut crate::ticker::Ticker,
+        fps: f32,
+        mut control: impl FnMut(
+            &mut crate::ticker::Ticker,
+            crate::pacing::Frame,
+        ) -> core::ops::ControlFlow<()>,
+    ) -> Result<(), Error> {
+        let mut buffer = DoubleBuffer::new(self.clone());
+        FrameLoop::new(fps)
+            .run(&mut buffer, async |buffer, frame| {
+                if control(ticker, frame).is_break() {
+                    return Ok(core::ops::ControlFlow::Break(()));
+                }
+                ticker.advance(frame.delta);
+                ticker.draw(buffer.back_mut());
+                if ticker.is_finished(buffer.width()) {
+                    Ok(core::ops::ControlFlow::Break(()))
+                } else {
+                    Ok(core::ops::ControlFlow::Continue(()))
+                }
+            })
+            .await
+    }
+}
+
+#[cfg(feature = "image")]
+impl Client {
+    /// Scale the image onto the display and send it via [`contiguous`](Self::contiguous).
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// [Dithering](crate::imaging::Dither) takes the configured [`Correction`](crate::correction::Correction) into account.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    pub async fn display_image(
+        &self,
+        image: &image::DynamicImage,
+        options: crate::imaging::ImageOptions,
+    ) -> Result<(), Error> {
+        let Some(placed) =
+            options.place_for(image, self.width, self.height, self.pipeline.correction())
+        else {
+            return Ok(());
+        };
+        self.contiguous(
+            placed.x,
+            placed.y,
+            placed.width,
+            placed.height,
+            &placed.colors,
+        )
+        .await
+    }
 
-        let expected_length = (width as usize) * (height as usize) * 3;
-        if expected_length != colors.len() {
-            return Err(std::io::Error::other("colors is wrong length"));
+    /// Play the animation and return when its [`LoopCount`](crate::animation::LoopCount) is exhausted.
+    ///
+    /// Only the difference to the previous frame is sent.
+    /// Frames are skipped when sending falls behind the delays of the animation.
+    /// The last frame stays on the display afterwards.
+    ///
+    /// Decode it with [`load_animation`](Self::load_animation) for dithering which takes the configured
+    /// [`Correction`](crate::correction::Correction) into account.
+    ///
+    /// # Errors
+    /// Errors when the animation size does not match the display or the commands could not be sent
+    pub async fn play_animation(
+        &self,
+        animation: &crate::animation::Animation,
+    ) -> Result<(), Error> {
+        if animation.width() != self.width || animation.height() != self.height {
+            return Err(Error::SizeMismatch {
+                display: (self.width, self.height),
+                content: (animation.width(), animation.height()),
+            });
+        }
+        let mut framebuffer = self.framebuffer();
+        let start = std::time::Instant::now();
+        let mut shown = None;
+        while let Some((index, remaining)) = animation.frame_at(start.elapsed()) {
+            let next = std::time::Instant::now() + remaining;
+            if shown != Some(index) {
+                framebuffer
+                    .pixels_mut()
+                    .copy_from_slice(&animation.frames()[index].pixels);
+                self.present(&mut framebuffer).await?;
+                shown = Some(index);
+            }
+            let wait = next.saturating_duration_since(std::time::Instant::now());
+            tokio::time::sleep(wait).await;
         }
+        // The last frame might have been skipped
+        let last = animation.frames().len().checked_sub(1);
+        if let Some(last) = last.filter(|last| shown != Some(*last)) {
+            framebuffer
+                .pixels_mut()
+                .copy_from_slice(&animation.frames()[last].pixels);
+            self.present(&mut framebuffer).await?;
+        }
+        Ok(())
+    }
 
-        let mut stream = self.stream.lock().await;
+    /// Decode an animated image in the size of the display for [`play_animation`](Self::play_animation).
+    ///
+    /// [Dithering](crate::imaging::Dither) takes the configured [`Correction`](crate::correction::Correction) into account.
+    /// Decoding blocks the current thread.
+    ///
+    /// # Errors
+    /// Errors when the image could not be decoded
+    pub fn load_animation(
+        &self,
+        bytes: &[u8],
+        options: crate::imaging::ImageOptions,
+    ) -> Result<crate::animation::Animation, Error> {
+        crate::animation::Animation::decode(
+            bytes,
+            self.width,
+            self.height,
+            options,
+            self.pipeline.correction(),
+        )
+    }
 
-        stream
-            .write_all(&[Command::Contiguous as u8, x, y, width, height])
-            .await?;
-        stream.write_all(colors).await
+    /// Load an animated image file like [`load_animation`](Self::load_animation).
+    ///
+    /// Loading blocks the current thread.
+    ///
+    /// # Errors
+    /// Errors when the file could not be read or decoded
+    pub fn load_animation_file<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        options: crate::imaging::ImageOptions,
+    ) -> Result<crate::animation::Animation, Error> {
+        let bytes = std::fs::read(path)?;
+        self.load_animation(&bytes, options)
+    }
+
+    /// Load an image file and show it like [`display_image`](Self::display_image).
+    ///
+    /// The format is detected from the content of the file.
+    /// Loading blocks the current thread.
+    ///
+    /// # Errors
+    /// Errors when the image could not be loaded or the command could not be sent
+    pub async fn display_image_file<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+        options: crate::imaging::ImageOptions,
+    ) -> Result<(), Error> {
+        let image = image::ImageReader::open(path)?
+            .with_guessed_format()?
+            .decode()?;
+        self.display_image(&image, options).await
     }
 }
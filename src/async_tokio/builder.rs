@@ -0,0 +1,141 @@
+use core::future::Future;
+use core::time::Duration;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+
+use tokio::io::{AsyncReadExt as _, BufStream};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::sync::Mutex;
+
+use crate::async_tokio::Client;
+use crate::correction::Correction;
+use crate::error::Error;
+use crate::pipeline::Pipeline;
+use crate::power::PowerBudget;
+use crate::protocol::{Bounds, Handshake};
+
+/// Configure how a [`Client`] connects and talks to the server.
+///
+/// Every timeout is disabled by default.
+/// Commands which do not fit on the display are rejected by default.
+/// An elapsed timeout results in an error of kind [`ErrorKind::TimedOut`],
+/// a refused connection in [`ErrorKind::ConnectionRefused`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct ClientBuilder {
+    connect_timeout: Option<Duration>,
+    handshake_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    bounds: Bounds,
+    correction: Option<Correction>,
+    power_budget: Option<PowerBudget>,
+}
+
+impl ClientBuilder {
+    pub const fn new() -> Self {
+        Self {
+            connect_timeout: None,
+            handshake_timeout: None,
+            write_timeout: None,
+            bounds: Bounds::Reject,
+            correction: None,
+            power_budget: None,
+        }
+    }
+
+    /// Timeout for establishing the TCP connection.
+    pub const fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for receiving the protocol version and display size after connecting.
+    pub const fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each write to the server. Relevant when flushing.
+    pub const fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// How to handle drawing commands which do not fit on the display. Defaults to [`Bounds::Reject`].
+    pub const fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Correct every color before sending it. See [`Correction`].
+    pub const fn correction(mut self, correction: Correction) -> Self {
+        self.correction = Some(correction);
+        self
+    }
+
+    /// Dim colors which would make the display exceed the [`PowerBudget`].
+    pub const fn power_budget(mut self, budget: PowerBudget) -> Self {
+        self.power_budget = Some(budget);
+        self
+    }
+
+    /// Connect to the server
+    ///
+    /// # Errors
+    /// Errors when the connection could not be established.
+    pub async fn connect<Address>(&self, address: Address) -> Result<Client, Error>
+    where
+        Address: ToSocketAddrs + Send,
+    {
+        let stream =
+            with_timeout(self.connect_timeout, "connect", TcpStream::connect(address)).await?;
+        let mut stream = BufStream::new(stream);
+
+        let mut handshake = [0; Handshake::LENGTH];
+        with_timeout(
+            self.handshake_timeout,
+            "handshake",
+            stream.read_exact(&mut handshake),
+        )
+        .await?;
+        let Handshake { width, height } = Handshake::decode(handshake)?;
+
+        Ok(Client {
+            stream: Arc::new(Mutex::new(Some(stream))),
+            width,
+            height,
+            write_timeout: self.write_timeout,
+            bytes_sent: Arc::new(AtomicU64::new(0)),
+            pipeline: Pipeline::new(
+                width,
+                height,
+                self.bounds,
+                self.correction.clone(),
+                self.power_budget,
+            ),
+        })
+    }
+}
+
+/// Run the future and fail with [`ErrorKind::TimedOut`] when it takes longer than the timeout.
+pub(super) async fn with_timeout<T, F>(
+    timeout: Option<Duration>,
+    operation: &str,
+    future: F,
+) -> std::io::Result<T>
+where
+    F: Future<Output = std::io::Result<T>>,
+{
+    let Some(timeout) = timeout else {
+        return future.await;
+    };
+    tokio::time::timeout(timeout, future)
+        .await
+        .unwrap_or_else(|_elapsed| {
+            Err(std::io::Error::new(
+                ErrorKind::TimedOut,
+                format!("{operation} timed out"),
+            ))
+        })
+}
@@ -0,0 +1,112 @@
+use crate::async_tokio::Client;
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+
+/// Draw into a back buffer and show it on the display all at once.
+///
+/// Commands reach the display as soon as they are flushed, so drawing directly can show half drawn frames.
+/// Here nothing is sent while drawing. [`present`](Self::present) sends the whole difference
+/// to the previous frame with a single write followed by a single flush.
+pub struct DoubleBuffer {
+    client: Client,
+    back: Framebuffer,
+}
+
+impl DoubleBuffer {
+    /// The display content is unknown, so the first present sends everything.
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        let back = client.framebuffer();
+        Self { client, back }
+    }
+
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// The frame which is drawn currently.
+    #[must_use]
+    pub const fn back(&self) -> &Framebuffer {
+        &self.back
+    }
+
+    /// Draw on the next frame, for example with `embedded-graphics`.
+    pub const fn back_mut(&mut self) -> &mut Framebuffer {
+        &mut self.back
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.back.width()
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.back.height()
+    }
+
+    /// Set one pixel of the next frame. Pixels outside of the display are ignored.
+    pub fn pixel(&mut self, x: u8, y: u8, color: Rgb) {
+        self.back.set_pixel(x, y, color.to_array());
+    }
+
+    /// Fill the whole next frame with one color.
+    pub fn fill(&mut self, color: Rgb) {
+        self.back.fill(color.to_array());
+    }
+
+    /// Fill an area of the next frame with one color. Parts outside of the display are ignored.
+    pub fn rectangle(&mut self, x: u8, y: u8, width: u8, height: u8, color: Rgb) {
+        self.back.rectangle(x, y, width, height, color.to_array());
+    }
+
+    /// Set an area of the next frame. Parts outside of the display are ignored.
+    ///
+    /// The area begins in the top left at x/y and moves first on the x axis, then on the y axis.
+    ///
+    /// # Errors
+    /// Errors when the amount of colors does not match the area
+    pub fn contiguous(
+        &mut self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        let expected = usize::from(width) * usize::from(height);
+        if expected != colors.len() {
+            return Err(Error::ColorBufferLength {
+                expected: expected * 3,
+                actual: colors.len() * 3,
+            });
+        }
+        if width == 0 {
+            return Ok(());
+        }
+        for (y, row) in (y..=u8::MAX).zip(colors.chunks_exact(width.into())) {
+            for (x, color) in (x..=u8::MAX).zip(row) {
+                self.back.set_pixel(x, y, color.to_array());
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the difference to the previous frame in one write and flush.
+    ///
+    /// The back buffer keeps its content, so the next frame can be drawn on top of it.
+    ///
+    /// # Errors
+    /// Errors when the commands could not be sent
+    pub async fn present(&mut self) -> Result<(), Error> {
+        self.client.present(&mut self.back).await
+    }
+
+    /// Send the whole frame on the next [`present`](Self::present),
+    /// for example when something else drew on the display.
+    pub fn invalidate(&mut self) {
+        self.back.invalidate();
+    }
+}
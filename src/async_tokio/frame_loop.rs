@@ -0,0 +1,153 @@
+use core::ops::ControlFlow;
+use std::time::Instant;
+
+use crate::async_tokio::{
+    Client, DoubleBuffer, ReconnectingClient, TiledDisplay, TransformedClient,
+};
+use crate::error::Error;
+use crate::pacing::{Frame, FrameStats, Pacer};
+
+/// Display which the [`FrameLoop`] flushes after every frame.
+#[expect(async_fn_in_trait)]
+pub trait FrameTarget {
+    /// Send everything drawn for the current frame.
+    ///
+    /// # Errors
+    /// Errors when the commands could not be sent
+    async fn flush_frame(&mut self) -> Result<(), Error>;
+
+    /// Amount of bytes written to the connection so far.
+    fn bytes_sent(&self) -> u64;
+}
+
+/// Render at a target frame rate, see [`Pacer`].
+///
+/// The time spent rendering and sending is taken into account.
+/// When a frame took too long the missed frames are skipped.
+///
+/// ```no_run
+/// # use core::ops::ControlFlow;
+/// # use esp_remotecontrolled_led_matrix_client::Rgb;
+/// # use esp_remotecontrolled_led_matrix_client::async_tokio::{Client, FrameLoop};
+/// # async fn example() -> Result<(), esp_remotecontrolled_led_matrix_client::Error> {
+/// let mut client = Client::connect("espPixelmatrix:1337").await?;
+/// let mut frame_loop = FrameLoop::new(30.0);
+/// frame_loop
+///     .run(&mut client, async |client, frame| {
+///         let hue = frame.elapsed.as_secs_f32() * 90.0;
+///         client.fill(Rgb::from_hsv(hue % 360.0, 1.0, 1.0)).await?;
+///         Ok(ControlFlow::Continue(()))
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameLoop {
+    pacer: Pacer,
+}
+
+impl FrameLoop {
+    #[must_use]
+    pub fn new(fps: f32) -> Self {
+        Self {
+            pacer: Pacer::new(fps),
+        }
+    }
+
+    #[must_use]
+    pub const fn stats(&self) -> &FrameStats {
+        self.pacer.stats()
+    }
+
+    /// Render frames until `render` returns [`ControlFlow::Break`].
+    ///
+    /// # Errors
+    /// Errors when `render` errors or the frame could not be sent
+    pub async fn run<Target: FrameTarget>(
+        &mut self,
+        target: &mut Target,
+        mut render: impl AsyncFnMut(&mut Target, Frame) -> Result<ControlFlow<()>, Error>,
+    ) -> Result<(), Error> {
+        while self.frame(target, &mut render).await?.is_continue() {}
+        Ok(())
+    }
+
+    /// Render and flush a single frame, then wait until the next one is due.
+    ///
+    /// # Errors
+    /// Errors when `render` errors or the frame could not be sent
+    pub async fn frame<Target: FrameTarget>(
+        &mut self,
+        target: &mut Target,
+        render: impl AsyncFnOnce(&mut Target, Frame) -> Result<ControlFlow<()>, Error>,
+    ) -> Result<ControlFlow<()>, Error> {
+        let frame = self.pacer.begin(Instant::now());
+        let bytes_before = target.bytes_sent();
+        let flow = render(target, frame).await?;
+        let flush_start = Instant::now();
+        target.flush_frame().await?;
+        let now = Instant::now();
+        let bytes = target.bytes_sent().saturating_sub(bytes_before);
+        let wait = self.pacer.finish(now, bytes, now - flush_start);
+        if flow.is_continue() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(flow)
+    }
+}
+
+impl FrameTarget for Client {
+    async fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush().await
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        Self::bytes_sent(self)
+    }
+}
+
+/// Every frame is [presented](DoubleBuffer::present).
+impl FrameTarget for DoubleBuffer {
+    async fn flush_frame(&mut self) -> Result<(), Error> {
+        self.present().await
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
+
+impl FrameTarget for TransformedClient {
+    async fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush().await
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
+
+impl FrameTarget for TiledDisplay {
+    async fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush().await
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.clients()
+            .iter()
+            .map(|client| client.client().bytes_sent())
+            .sum()
+    }
+}
+
+/// The amount of bytes starts over with every reconnect.
+impl FrameTarget for ReconnectingClient {
+    async fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush().await
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
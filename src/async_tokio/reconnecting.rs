@@ -0,0 +1,235 @@
+use tokio::time::sleep;
+
+use crate::async_tokio::{Client, ClientBuilder};
+use crate::backoff::Backoff;
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::protocol::Message;
+
+/// [`Client`] which transparently reconnects when the connection is lost.
+///
+/// Each command which fails because of the connection is retried once after reconnecting.
+/// Commands which were buffered but not yet flushed when the connection was lost are gone.
+/// Enable [`replay`](Self::replay) to restore the last presented [`Framebuffer`] after reconnecting.
+pub struct ReconnectingClient {
+    address: String,
+    builder: ClientBuilder,
+    backoff: Backoff,
+    client: Client,
+    replay: bool,
+    last_frame: Option<Framebuffer>,
+}
+
+impl ReconnectingClient {
+    /// Connect to the server, retrying with the given backoff until it succeeds.
+    ///
+    /// # Errors
+    /// Errors when [`Backoff::max_attempts`] is exceeded.
+    pub async fn connect<Address: Into<String>>(
+        address: Address,
+        backoff: Backoff,
+    ) -> Result<Self, Error> {
+        Self::connect_with_builder(address, ClientBuilder::new(), backoff).await
+    }
+
+    /// Connect to the server with the timeouts of the [`ClientBuilder`], retrying with the given backoff until it succeeds.
+    ///
+    /// The builder is also used for every reconnect.
+    ///
+    /// # Errors
+    /// Errors when [`Backoff::max_attempts`] is exceeded.
+    pub async fn connect_with_builder<Address: Into<String>>(
+        address: Address,
+        builder: ClientBuilder,
+        backoff: Backoff,
+    ) -> Result<Self, Error> {
+        let address = address.into();
+        let client = connect_with_backoff(&address, &builder, &backoff).await?;
+        Ok(Self {
+            address,
+            builder,
+            backoff,
+            client,
+            replay: false,
+            last_frame: None,
+        })
+    }
+
+    /// Show the last presented [`Framebuffer`] again after reconnecting.
+    #[must_use]
+    pub const fn replay(mut self, replay: bool) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    /// The currently connected client.
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Width of the display. Reread on every reconnect.
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.client.width()
+    }
+
+    /// Height of the display. Reread on every reconnect.
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.client.height()
+    }
+
+    #[must_use]
+    pub const fn total_pixels(&self) -> u16 {
+        self.client.total_pixels()
+    }
+
+    /// Create a [`Framebuffer`] with the size of the display.
+    #[must_use]
+    pub fn framebuffer(&self) -> Framebuffer {
+        self.client.framebuffer()
+    }
+
+    /// Replace the connection with a new one.
+    ///
+    /// # Errors
+    /// Errors when [`Backoff::max_attempts`] is exceeded or the last frame could not be replayed.
+    pub async fn reconnect(&mut self) -> Result<(), Error> {
+        self.reconnect_without_replay().await?;
+        if let Some(frame) = &mut self.last_frame {
+            if frame.width() == self.client.width() && frame.height() == self.client.height() {
+                frame.invalidate();
+                self.client.present(frame).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the connection without sending the last frame again.
+    async fn reconnect_without_replay(&mut self) -> Result<(), Error> {
+        self.client = connect_with_backoff(&self.address, &self.builder, &self.backoff).await?;
+        Ok(())
+    }
+
+    async fn retry<T>(
+        &mut self,
+        operation: impl AsyncFn(&Client) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        match operation(&self.client).await {
+            Err(error) if error.is_connection_error() => {
+                self.reconnect().await?;
+                operation(&self.client).await
+            }
+            result => result,
+        }
+    }
+
+    /// See [`Client::flush`]
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent after reconnecting
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.retry(Client::flush).await
+    }
+
+    /// See [`Client::present`]
+    ///
+    /// After reconnecting the whole framebuffer is sent again.
+    ///
+    /// # Errors
+    /// Errors when the framebuffer size does not match the display or the commands could not be sent after reconnecting
+    pub async fn present(&mut self, framebuffer: &mut Framebuffer) -> Result<(), Error> {
+        match self.client.present(framebuffer).await {
+            Err(error) if error.is_connection_error() => {
+                // The whole new frame follows, replaying the last one would only flash it
+                self.reconnect_without_replay().await?;
+                framebuffer.invalidate();
+                self.client.present(framebuffer).await?;
+            }
+            result => result?,
+        }
+        if self.replay {
+            self.last_frame = Some(framebuffer.clone());
+        }
+        Ok(())
+    }
+
+    /// See [`Client::send`]
+    ///
+    /// # Errors
+    /// Errors when the data could not be written to the send buffer after reconnecting
+    pub async fn send(&mut self, message: &Message) -> Result<(), Error> {
+        self.retry(async |client| client.send(message).await).await
+    }
+
+    /// See [`Client::pixel`]
+    ///
+    /// # Errors
+    /// Errors when the data could not be written to the send buffer after reconnecting
+    pub async fn pixel(&mut self, x: u8, y: u8, color: Rgb) -> Result<(), Error> {
+        self.retry(async |client| client.pixel(x, y, color).await)
+            .await
+    }
+
+    /// See [`Client::fill`]
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent after reconnecting
+    pub async fn fill(&mut self, color: Rgb) -> Result<(), Error> {
+        self.retry(async |client| client.fill(color).await).await
+    }
+
+    /// See [`Client::rectangle`]
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent after reconnecting
+    pub async fn rectangle(
+        &mut self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        color: Rgb,
+    ) -> Result<(), Error> {
+        self.retry(async |client| client.rectangle(x, y, width, height, color).await)
+            .await
+    }
+
+    /// See [`Client::contiguous`]
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent after reconnecting
+    pub async fn contiguous(
+        &mut self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        self.retry(async |client| client.contiguous(x, y, width, height, colors).await)
+            .await
+    }
+}
+
+async fn connect_with_backoff(
+    address: &str,
+    builder: &ClientBuilder,
+    backoff: &Backoff,
+) -> Result<Client, Error> {
+    let mut failed_attempts = 0;
+    loop {
+        match builder.connect(address).await {
+            Ok(client) => return Ok(client),
+            Err(error) => {
+                failed_attempts += 1;
+                if !backoff.should_retry(failed_attempts) {
+                    return Err(error);
+                }
+                sleep(backoff.delay(failed_attempts)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use core::time::Duration;
+
+/// Exponential backoff between reconnection attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay after the first failed attempt.
+    pub initial: Duration,
+    /// Upper bound of the delay.
+    pub max: Duration,
+    /// The delay is multiplied by this factor after each failed attempt.
+    pub factor: u32,
+    /// Give up after this many failed attempts. `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+            factor: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay to wait after the given amount of failed attempts.
+    #[must_use]
+    pub fn delay(&self, failed_attempts: u32) -> Duration {
+        let factor = self
+            .factor
+            .checked_pow(failed_attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Whether another attempt should be made after the given amount of failed attempts.
+    #[must_use]
+    pub fn should_retry(&self, failed_attempts: u32) -> bool {
+        self.max_attempts
+            .is_none_or(|max_attempts| failed_attempts < max_attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_until_max() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            factor: 3,
+            max_attempts: None,
+        };
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(300));
+        assert_eq!(backoff.delay(3), Duration::from_millis(900));
+        assert_eq!(backoff.delay(4), Duration::from_secs(1));
+        assert_eq!(backoff.delay(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn max_attempts() {
+        let backoff = Backoff {
+            max_attempts: Some(2),
+            ..Backoff::default()
+        };
+        assert!(backoff.should_retry(0));
+        assert!(backoff.should_retry(1));
+        assert!(!backoff.should_retry(2));
+        assert!(Backoff::default().should_retry(u32::MAX));
+    }
+}
@@ -0,0 +1,126 @@
+//! Software stand-in for an espPixelmatrix rendering into the terminal.
+//!
+//! Run it with `cargo run --features mock --bin esp-matrix-emulator -- --size 32x8 --port 1337`
+//! and point a client to `localhost:1337`.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::time::Duration;
+
+use esp_remotecontrolled_led_matrix_client::mock::MockServer;
+
+/// Upper bound of terminal redraws to keep fast clients from flooding the terminal.
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
+const USAGE: &str =
+    "Usage: esp-matrix-emulator [--size <WIDTHxHEIGHT>] [--port <PORT>] [--bind <ADDRESS>]
+
+Options:
+  --size <WIDTHxHEIGHT>  Size of the emulated matrix [default: 32x8]
+  --port <PORT>          TCP port to listen on [default: 1337]
+  --bind <ADDRESS>       Address to listen on [default: 127.0.0.1]
+  --help                 Print this help";
+
+struct Args {
+    width: u8,
+    height: u8,
+    port: u16,
+    bind: String,
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(err) => {
+            eprintln!("ERROR: {err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let server = MockServer::bind((args.bind.as_str(), args.port), args.width, args.height)
+        .expect("failed to bind");
+
+    // Clear the screen
+    print!("\x1b[2J");
+    render(&server);
+
+    let mut seen = 0;
+    loop {
+        if !server.wait_for_messages(seen + 1, Duration::from_secs(1)) {
+            continue;
+        }
+        std::thread::sleep(FRAME_TIME);
+        seen = server.received();
+        // Only the framebuffer is of interest, do not keep every message forever
+        drop(server.take_messages());
+        render(&server);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Args>, String> {
+    let mut result = Args {
+        width: 32,
+        height: 8,
+        port: 1337,
+        bind: "127.0.0.1".to_owned(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--size" => {
+                let value = args.next().ok_or("--size requires a value")?;
+                let (width, height) = value
+                    .split_once(['x', 'X'])
+                    .ok_or("--size has to be in the format WIDTHxHEIGHT")?;
+                result.width = width.parse().map_err(|_| "invalid width")?;
+                result.height = height.parse().map_err(|_| "invalid height")?;
+            }
+            "--port" => {
+                let value = args.next().ok_or("--port requires a value")?;
+                result.port = value.parse().map_err(|_| "invalid port")?;
+            }
+            "--bind" => {
+                result.bind = args.next().ok_or("--bind requires a value")?;
+            }
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    Ok(Some(result))
+}
+
+/// Draw two rows of pixels per terminal line with the upper half block.
+/// The foreground color is the upper pixel, the background color the lower pixel.
+fn render(server: &MockServer) {
+    let width = usize::from(server.width());
+    let pixels = server.framebuffer();
+
+    let mut output = String::from("\x1b[H");
+    for rows in pixels.chunks((width * 2).max(1)) {
+        let (upper, lower) = rows.split_at(width.min(rows.len()));
+        for (x, [red, green, blue]) in upper.iter().enumerate() {
+            _ = write!(output, "\x1b[38;2;{red};{green};{blue}m");
+            if let Some([red, green, blue]) = lower.get(x) {
+                _ = write!(output, "\x1b[48;2;{red};{green};{blue}m");
+            } else {
+                output += "\x1b[49m";
+            }
+            output += "▀";
+        }
+        output += "\x1b[0m\n";
+    }
+    _ = writeln!(
+        output,
+        "{}x{} listening on {}",
+        server.width(),
+        server.height(),
+        server.address()
+    );
+
+    let mut stdout = std::io::stdout().lock();
+    _ = stdout.write_all(output.as_bytes());
+    _ = stdout.flush();
+}
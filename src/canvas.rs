@@ -0,0 +1,145 @@
+//! Clip drawing commands with coordinates beyond the protocol onto a display.
+//!
+//! The clients provide this as the `Canvas` trait which accepts negative and large coordinates.
+//! Everything outside of the display is cut away before it is mapped onto the `u8` protocol.
+
+use crate::color::Rgb;
+
+/// Part of an area which is visible on the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clipped {
+    /// Position on the display
+    pub x: u16,
+    /// Position on the display
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Amount of columns cut away on the left
+    pub skip_x: u32,
+    /// Amount of rows cut away on the top
+    pub skip_y: u32,
+}
+
+impl Clipped {
+    /// Take the visible colors out of the colors of the whole area.
+    #[must_use]
+    pub fn colors(&self, colors: &[Rgb], area_width: u32) -> Vec<Rgb> {
+        let Ok(area_width) = usize::try_from(area_width) else {
+            return Vec::new();
+        };
+        let skip_x = self.skip_x as usize;
+        let skip_y = self.skip_y as usize;
+        colors
+            .chunks_exact(area_width.max(1))
+            .skip(skip_y)
+            .take(self.height.into())
+            .flat_map(|row| &row[skip_x..skip_x + usize::from(self.width)])
+            .copied()
+            .collect()
+    }
+}
+
+/// Cut the area down to the part visible on a display of the given size.
+///
+/// Returns `None` when nothing is visible.
+#[must_use]
+pub fn clip(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    display_width: u16,
+    display_height: u16,
+) -> Option<Clipped> {
+    let (x, skip_x, width) = clip_axis(x, width, display_width)?;
+    let (y, skip_y, height) = clip_axis(y, height, display_height)?;
+    Some(Clipped {
+        x,
+        y,
+        width,
+        height,
+        skip_x,
+        skip_y,
+    })
+}
+
+fn clip_axis(start: i32, length: u32, display: u16) -> Option<(u16, u32, u16)> {
+    let start = i64::from(start);
+    let end = start + i64::from(length);
+    let visible_start = start.max(0);
+    let visible_end = end.min(i64::from(display));
+    if visible_start >= visible_end {
+        return None;
+    }
+    Some((
+        u16::try_from(visible_start).ok()?,
+        u32::try_from(visible_start - start).ok()?,
+        u16::try_from(visible_end - visible_start).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inside() {
+        assert_eq!(
+            clip(1, 2, 3, 4, 10, 10),
+            Some(Clipped {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+                skip_x: 0,
+                skip_y: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn partially_outside() {
+        assert_eq!(
+            clip(-2, 8, 5, 100, 10, 10),
+            Some(Clipped {
+                x: 0,
+                y: 8,
+                width: 3,
+                height: 2,
+                skip_x: 2,
+                skip_y: 0,
+            })
+        );
+        assert_eq!(
+            clip(i32::MIN, -1, u32::MAX, 2, 300, 10),
+            Some(Clipped {
+                x: 0,
+                y: 0,
+                width: 300,
+                height: 1,
+                skip_x: 2_147_483_648,
+                skip_y: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn outside() {
+        assert_eq!(clip(-5, 0, 5, 1, 10, 10), None);
+        assert_eq!(clip(10, 0, 5, 1, 10, 10), None);
+        assert_eq!(clip(0, 0, 0, 1, 10, 10), None);
+        assert_eq!(clip(0, 0, 1, 1, 0, 10), None);
+    }
+
+    #[test]
+    fn visible_colors() {
+        let colors = (0..6)
+            .map(|value| Rgb::new(value, 0, 0))
+            .collect::<Vec<_>>();
+        let clipped = clip(-1, -1, 3, 2, 10, 10).unwrap();
+        assert_eq!(
+            clipped.colors(&colors, 3),
+            [Rgb::new(4, 0, 0), Rgb::new(5, 0, 0)]
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Colors accepted by the clients.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::Error;
+
+/// A color as shown by the LEDs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const BLUE: Self = Self::new(0, 0, 255);
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const RED: Self = Self::new(255, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    #[must_use]
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Create a color from its `0xRRGGBB` representation. The highest byte is ignored.
+    #[must_use]
+    pub const fn from_u32(rgb: u32) -> Self {
+        let [_, red, green, blue] = rgb.to_be_bytes();
+        Self::new(red, green, blue)
+    }
+
+    /// Convert from hue (degrees), saturation and value (both 0.0 to 1.0).
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        Self::from_chroma(hue, chroma, value - chroma)
+    }
+
+    /// Convert from hue (degrees), saturation and lightness (both 0.0 to 1.0).
+    #[must_use]
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - 2.0f32.mul_add(lightness, -1.0).abs()) * saturation;
+        Self::from_chroma(hue, chroma, chroma.mul_add(-0.5, lightness))
+    }
+
+    fn from_chroma(hue: f32, chroma: f32, offset: f32) -> Self {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let intermediate = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (red, green, blue) = match sector {
+            ..1.0 => (chroma, intermediate, 0.0),
+            ..2.0 => (intermediate, chroma, 0.0),
+            ..3.0 => (0.0, chroma, intermediate),
+            ..4.0 => (0.0, intermediate, chroma),
+            ..5.0 => (intermediate, 0.0, chroma),
+            _ => (chroma, 0.0, intermediate),
+        };
+        Self::new(
+            channel(red + offset),
+            channel(green + offset),
+            channel(blue + offset),
+        )
+    }
+
+    /// Look up a named CSS color like `rebeccapurple`. The name is case-insensitive.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        CSS_COLORS
+            .iter()
+            .find(|(css_name, _)| css_name.eq_ignore_ascii_case(name))
+            .map(|&(_, rgb)| Self::from_u32(rgb))
+    }
+
+    /// Parse `#rrggbb` or `#rgb` hex notation.
+    ///
+    /// # Errors
+    /// Errors when the text is not a valid hex color.
+    pub fn from_hex(text: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidColor(text.to_owned());
+        let digits = text.strip_prefix('#').ok_or_else(invalid)?;
+        if !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        match digits.len() {
+            6 => Ok(Self::from_u32(value)),
+            3 => {
+                let [_, _, red, green_blue] = value.to_be_bytes();
+                Ok(Self::new(
+                    red * 0x11,
+                    (green_blue >> 4) * 0x11,
+                    (green_blue & 0xf) * 0x11,
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_array(self) -> [u8; 3] {
+        [self.red, self.green, self.blue]
+    }
+}
+
+#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Parses `#rrggbb`, `#rgb` or a named CSS color.
+impl FromStr for Rgb {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.starts_with('#') {
+            Self::from_hex(text)
+        } else {
+            Self::from_name(text).ok_or_else(|| Error::InvalidColor(text.to_owned()))
+        }
+    }
+}
+
+/// Formats as `#rrggbb`.
+impl fmt::Display for Rgb {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+impl From<[u8; 3]> for Rgb {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        Self::new(red, green, blue)
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgb {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Self::new(red, green, blue)
+    }
+}
+
+impl From<Rgb> for [u8; 3] {
+    fn from(rgb: Rgb) -> Self {
+        rgb.to_array()
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics {
+    use embedded_graphics::pixelcolor::{Rgb888, RgbColor as _};
+
+    use super::Rgb;
+
+    impl From<Rgb888> for Rgb {
+        fn from(color: Rgb888) -> Self {
+            Self::new(color.r(), color.g(), color.b())
+        }
+    }
+
+    impl From<Rgb> for Rgb888 {
+        fn from(rgb: Rgb) -> Self {
+            Self::new(rgb.red, rgb.green, rgb.blue)
+        }
+    }
+}
+
+const CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0x00f0_f8ff),
+    ("antiquewhite", 0x00fa_ebd7),
+    ("aqua", 0x0000_ffff),
+    ("aquamarine", 0x007f_ffd4),
+    ("azure", 0x00f0_ffff),
+    ("beige", 0x00f5_f5dc),
+    ("bisque", 0x00ff_e4c4),
+    ("black", 0x0000_0000),
+    ("blanchedalmond", 0x00ff_ebcd),
+    ("blue", 0x0000_00ff),
+    ("blueviolet", 0x008a_2be2),
+    ("brown", 0x00a5_2a2a),
+    ("burlywood", 0x00de_b887),
+    ("cadetblue", 0x005f_9ea0),
+    ("chartreuse", 0x007f_ff00),
+    ("chocolate", 0x00d2_691e),
+    ("coral", 0x00ff_7f50),
+    ("cornflowerblue", 0x0064_95ed),
+    ("cornsilk", 0x00ff_f8dc),
+    ("crimson", 0x00dc_143c),
+    ("cyan", 0x0000_ffff),
+    ("darkblue", 0x0000_008b),
+    ("darkcyan", 0x0000_8b8b),
+    ("darkgoldenrod", 0x00b8_860b),
+    ("darkgray", 0x00a9_a9a9),
+    ("darkgreen", 0x0000_6400),
+    ("darkgrey", 0x00a9_a9a9),
+    ("darkkhaki", 0x00bd_b76b),
+    ("darkmagenta", 0x008b_008b),
+    ("darkolivegreen", 0x0055_6b2f),
+    ("darkorange", 0x00ff_8c00),
+    ("darkorchid", 0x0099_32cc),
+    ("darkred", 0x008b_0000),
+    ("darksalmon", 0x00e9_967a),
+    ("darkseagreen", 0x008f_bc8f),
+    ("darkslateblue", 0x0048_3d8b),
+    ("darkslategray", 0x002f_4f4f),
+    ("darkslategrey", 0x002f_4f4f),
+    ("darkturquoise", 0x0000_ced1),
+    ("darkviolet", 0x0094_00d3),
+    ("deeppink", 0x00ff_1493),
+    ("deepskyblue", 0x0000_bfff),
+    ("dimgray", 0x0069_6969),
+    ("dimgrey", 0x0069_6969),
+    ("dodgerblue", 0x001e_90ff),
+    ("firebrick", 0x00b2_2222),
+    ("floralwhite", 0x00ff_faf0),
+    ("forestgreen", 0x0022_8b22),
+    ("fuchsia", 0x00ff_00ff),
+    ("gainsboro", 0x00dc_dcdc),
+    ("ghostwhite", 0x00f8_f8ff),
+    ("gold", 0x00ff_d700),
+    ("goldenrod", 0x00da_a520),
+    ("gray", 0x0080_8080),
+    ("green", 0x0000_8000),
+    ("greenyellow", 0x00ad_ff2f),
+    ("grey", 0x0080_8080),
+    ("honeydew", 0x00f0_fff0),
+    ("hotpink", 0x00ff_69b4),
+    ("indianred", 0x00cd_5c5c),
+    ("indigo", 0x004b_0082),
+    ("ivory", 0x00ff_fff0),
+    ("khaki", 0x00f0_e68c),
+    ("lavender", 0x00e6_e6fa),
+    ("lavenderblush", 0x00ff_f0f5),
+    ("lawngreen", 0x007c_fc00),
+    ("lemonchiffon", 0x00ff_facd),
+    ("lightblue", 0x00ad_d8e6),
+    ("lightcoral", 0x00f0_8080),
+    ("lightcyan", 0x00e0_ffff),
+    ("lightgoldenrodyellow", 0x00fa_fad2),
+    ("lightgray", 0x00d3_d3d3),
+    ("lightgreen", 0x0090_ee90),
+    ("lightgrey", 0x00d3_d3d3),
+    ("lightpink", 0x00ff_b6c1),
+    ("lightsalmon", 0x00ff_a07a),
+    ("lightseagreen", 0x0020_b2aa),
+    ("lightskyblue", 0x0087_cefa),
+    ("lightslategray", 0x0077_8899),
+    ("lightslategrey", 0x0077_8899),
+    ("lightsteelblue", 0x00b0_c4de),
+    ("lightyellow", 0x00ff_ffe0),
+    ("lime", 0x0000_ff00),
+    ("limegreen", 0x0032_cd32),
+    ("linen", 0x00fa_f0e6),
+    ("magenta", 0x00ff_00ff),
+    ("maroon", 0x0080_0000),
+    ("mediumaquamarine", 0x0066_cdaa),
+    ("mediumblue", 0x0000_00cd),
+    ("mediumorchid", 0x00ba_55d3),
+    ("mediumpurple", 0x0093_70db),
+    ("mediumseagreen", 0x003c_b371),
+    ("mediumslateblue", 0x007b_68ee),
+    ("mediumspringgreen", 0x0000_fa9a),
+    ("mediumturquoise", 0x0048_d1cc),
+    ("mediumvioletred", 0x00c7_1585),
+    ("midnightblue", 0x0019_1970),
+    ("mintcream", 0x00f5_fffa),
+    ("mistyrose", 0x00ff_e4e1),
+    ("moccasin", 0x00ff_e4b5),
+    ("navajowhite", 0x00ff_dead),
+    ("navy", 0x0000_0080),
+    ("oldlace", 0x00fd_f5e6),
+    ("olive", 0x0080_8000),
+    ("olivedrab", 0x006b_8e23),
+    ("orange", 0x00ff_a500),
+    ("orangered", 0x00ff_4500),
+    ("orchid", 0x00da_70d6),
+    ("palegoldenrod", 0x00ee_e8aa),
+    ("palegreen", 0x0098_fb98),
+    ("paleturquoise", 0x00af_eeee),
+    ("palevioletred", 0x00db_7093),
+    ("papayawhip", 0x00ff_efd5),
+    ("peachpuff", 0x00ff_dab9),
+    ("peru", 0x00cd_853f),
+    ("pink", 0x00ff_c0cb),
+    ("plum", 0x00dd_a0dd),
+    ("powderblue", 0x00b0_e0e6),
+    ("purple", 0x0080_0080),
+    ("rebeccapurple", 0x0066_3399),
+    ("red", 0x00ff_0000),
+    ("rosybrown", 0x00bc_8f8f),
+    ("royalblue", 0x0041_69e1),
+    ("saddlebrown", 0x008b_4513),
+    ("salmon", 0x00fa_8072),
+    ("sandybrown", 0x00f4_a460),
+    ("seagreen", 0x002e_8b57),
+    ("seashell", 0x00ff_f5ee),
+    ("sienna", 0x00a0_522d),
+    ("silver", 0x00c0_c0c0),
+    ("skyblue", 0x0087_ceeb),
+    ("slateblue", 0x006a_5acd),
+    ("slategray", 0x0070_8090),
+    ("slategrey", 0x0070_8090),
+    ("snow", 0x00ff_fafa),
+    ("springgreen", 0x0000_ff7f),
+    ("steelblue", 0x0046_82b4),
+    ("tan", 0x00d2_b48c),
+    ("teal", 0x0000_8080),
+    ("thistle", 0x00d8_bfd8),
+    ("tomato", 0x00ff_6347),
+    ("turquoise", 0x0040_e0d0),
+    ("violet", 0x00ee_82ee),
+    ("wheat", 0x00f5_deb3),
+    ("white", 0x00ff_ffff),
+    ("whitesmoke", 0x00f5_f5f5),
+    ("yellow", 0x00ff_ff00),
+    ("yellowgreen", 0x009a_cd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsv() {
+        assert_eq!(Rgb::from_hsv(0.0, 1.0, 1.0), Rgb::RED);
+        assert_eq!(Rgb::from_hsv(120.0, 1.0, 1.0), Rgb::GREEN);
+        assert_eq!(Rgb::from_hsv(240.0, 1.0, 1.0), Rgb::BLUE);
+        assert_eq!(Rgb::from_hsv(-120.0, 1.0, 1.0), Rgb::BLUE);
+        assert_eq!(Rgb::from_hsv(60.0, 1.0, 0.5), Rgb::new(128, 128, 0));
+        assert_eq!(Rgb::from_hsv(300.0, 0.0, 1.0), Rgb::WHITE);
+        assert_eq!(Rgb::from_hsv(30.0, 1.0, 0.0), Rgb::BLACK);
+    }
+
+    #[test]
+    fn hsl() {
+        assert_eq!(Rgb::from_hsl(0.0, 1.0, 0.5), Rgb::RED);
+        assert_eq!(Rgb::from_hsl(180.0, 1.0, 0.25), Rgb::new(0, 128, 128));
+        assert_eq!(Rgb::from_hsl(270.0, 0.5, 0.4), Rgb::from_u32(0x0066_3399));
+        assert_eq!(Rgb::from_hsl(0.0, 1.0, 1.0), Rgb::WHITE);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("#ff8000".parse::<Rgb>().unwrap(), Rgb::new(255, 128, 0));
+        assert_eq!("#F80".parse::<Rgb>().unwrap(), Rgb::new(255, 136, 0));
+        assert_eq!(
+            "RebeccaPurple".parse::<Rgb>().unwrap(),
+            Rgb::new(102, 51, 153)
+        );
+        for invalid in ["ff8000", "#ff80", "#ff800g", "#+ff800", "notacolor", ""] {
+            assert!(
+                matches!(invalid.parse::<Rgb>(), Err(Error::InvalidColor(_))),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        let rgb = Rgb::new(1, 171, 255);
+        assert_eq!(rgb.to_string(), "#01abff");
+        assert_eq!(rgb.to_string().parse::<Rgb>().unwrap(), rgb);
+    }
+
+    #[test]
+    fn css_colors_are_sorted_lowercase() {
+        assert!(CSS_COLORS.is_sorted_by_key(|(name, _)| *name));
+        assert!(
+            CSS_COLORS
+                .iter()
+                .all(|(name, _)| name.bytes().all(|byte| byte.is_ascii_lowercase()))
+        );
+    }
+}
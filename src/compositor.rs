@@ -0,0 +1,363 @@
+//! Combine layers like a background animation, a notification overlay and a ticker into one image.
+//!
+//! The clients send the result with `display_composition`.
+
+use crate::color::Rgb;
+
+/// How the colors of a [`Layer`] are combined with everything below it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The layer covers what is below.
+    #[default]
+    Normal,
+    /// The colors are summed up which only ever brightens.
+    Add,
+    /// The colors are multiplied which only ever darkens.
+    Multiply,
+}
+
+impl BlendMode {
+    /// Color resulting from the layer on top of the color below, both between 0.0 and 1.0.
+    fn blend(self, below: f32, layer: f32) -> f32 {
+        match self {
+            Self::Normal => layer,
+            Self::Add => (below + layer).min(1.0),
+            Self::Multiply => below * layer,
+        }
+    }
+}
+
+/// RGBA image placed on a [`Compositor`].
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Layer {
+    width: u8,
+    height: u8,
+    x: i16,
+    y: i16,
+    opacity: f32,
+    blend_mode: BlendMode,
+    visible: bool,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Layer {
+    /// Fully transparent layer in the top left.
+    pub fn new(width: u8, height: u8) -> Self {
+        Self {
+            width,
+            height,
+            x: 0,
+            y: 0,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+            visible: true,
+            pixels: vec![[0; 4]; usize::from(width) * usize::from(height)],
+        }
+    }
+
+    /// Position of the top left corner on the display. Parts outside of the display are not shown.
+    pub const fn with_offset(mut self, x: i16, y: i16) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Between 0.0 for invisible and 1.0 for the alpha of the pixels as they are.
+    pub const fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub const fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn offset(&self) -> (i16, i16) {
+        (self.x, self.y)
+    }
+
+    /// Move the layer, for example to slide in a notification.
+    pub const fn set_offset(&mut self, x: i16, y: i16) {
+        self.x = x;
+        self.y = y;
+    }
+
+    #[must_use]
+    pub const fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    #[must_use]
+    pub const fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Change the opacity, for example to fade the layer in or out.
+    pub const fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    #[must_use]
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Hidden layers are skipped while keeping their content and place in the order.
+    pub const fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Red, green, blue and alpha of every pixel, row by row starting in the top left.
+    #[must_use]
+    pub fn pixels(&self) -> &[[u8; 4]] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [[u8; 4]] {
+        &mut self.pixels
+    }
+
+    #[must_use]
+    pub fn pixel(&self, x: u8, y: u8) -> Option<[u8; 4]> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Pixels outside of the layer are ignored.
+    pub fn set_pixel(&mut self, x: u8, y: u8, rgba: [u8; 4]) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = rgba;
+        }
+    }
+
+    /// Set every pixel to the same color and alpha.
+    pub fn fill(&mut self, rgba: [u8; 4]) {
+        self.pixels.fill(rgba);
+    }
+
+    /// Make every pixel fully transparent.
+    pub fn clear(&mut self) {
+        self.fill([0; 4]);
+    }
+
+    /// Take over opaque pixels like the ones of a [`Framebuffer`](crate::framebuffer::Framebuffer)
+    /// or an animation frame of the same size.
+    ///
+    /// Pixels beyond the size of the layer are ignored.
+    pub fn copy_from(&mut self, pixels: &[[u8; 3]]) {
+        for (target, [red, green, blue]) in self.pixels.iter_mut().zip(pixels) {
+            *target = [*red, *green, *blue, u8::MAX];
+        }
+    }
+
+    /// Like [`copy_from`](Self::copy_from) but pixels of the `key` color become transparent.
+    ///
+    /// This allows to lay for example a ticker drawn on black over something else.
+    pub fn copy_keyed(&mut self, pixels: &[[u8; 3]], key: [u8; 3]) {
+        for (target, rgb) in self.pixels.iter_mut().zip(pixels) {
+            let [red, green, blue] = *rgb;
+            let alpha = if *rgb == key { 0 } else { u8::MAX };
+            *target = [red, green, blue, alpha];
+        }
+    }
+
+    fn index(&self, x: u8, y: u8) -> Option<usize> {
+        (x < self.width && y < self.height)
+            .then(|| usize::from(y) * usize::from(self.width) + usize::from(x))
+    }
+}
+
+/// Ordered [`Layer`]s on an opaque background, the first layer at the bottom.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Compositor {
+    width: u8,
+    height: u8,
+    background: Rgb,
+    layers: Vec<Layer>,
+}
+
+impl Compositor {
+    /// Without layers on black.
+    pub const fn new(width: u8, height: u8) -> Self {
+        Self {
+            width,
+            height,
+            background: Rgb::BLACK,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Shown where the layers are transparent.
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Add a layer on top of all others.
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// Layers from the bottom to the top.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Change the layers or their order. The first layer is at the bottom.
+    pub const fn layers_mut(&mut self) -> &mut Vec<Layer> {
+        &mut self.layers
+    }
+
+    /// Blend all visible layers onto the background.
+    ///
+    /// Returns every pixel of the compositor row by row starting in the top left,
+    /// ready to be sent with `contiguous`.
+    #[must_use]
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn compose(&self) -> Vec<Rgb> {
+        let background = self
+            .background
+            .to_array()
+            .map(|value| f32::from(value) / 255.0);
+        let mut result = vec![background; usize::from(self.width) * usize::from(self.height)];
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            self.blend_layer(&mut result, layer);
+        }
+        result
+            .into_iter()
+            .map(|color| {
+                let [red, green, blue] = color.map(|value| (value * 255.0).round() as u8);
+                Rgb::new(red, green, blue)
+            })
+            .collect()
+    }
+
+    fn blend_layer(&self, result: &mut [[f32; 3]], layer: &Layer) {
+        let width = i32::from(self.width);
+        let height = i32::from(self.height);
+        let rows = layer.pixels.chunks_exact(usize::from(layer.width).max(1));
+        for (y, row) in (i32::from(layer.y)..).zip(rows) {
+            if !(0..height).contains(&y) {
+                continue;
+            }
+            for (x, [red, green, blue, alpha]) in (i32::from(layer.x)..).zip(row) {
+                if !(0..width).contains(&x) || *alpha == 0 {
+                    continue;
+                }
+                let alpha = f32::from(*alpha) / 255.0 * layer.opacity;
+                let Ok(index) = usize::try_from(y * width + x) else {
+                    continue;
+                };
+                let below = &mut result[index];
+                for (below, value) in below.iter_mut().zip([red, green, blue]) {
+                    let blended = layer.blend_mode.blend(*below, f32::from(*value) / 255.0);
+                    *below = blended.mul_add(alpha, *below * (1.0 - alpha));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opaque(width: u8, height: u8, rgb: [u8; 3]) -> Layer {
+        let mut layer = Layer::new(width, height);
+        let [red, green, blue] = rgb;
+        layer.fill([red, green, blue, 255]);
+        layer
+    }
+
+    #[test]
+    fn background_without_layers() {
+        let compositor = Compositor::new(2, 1).background(Rgb::new(1, 2, 3));
+        assert_eq!(compositor.compose(), [Rgb::new(1, 2, 3); 2]);
+    }
+
+    #[test]
+    fn normal_covers_with_alpha_and_opacity() {
+        let mut half = Layer::new(3, 1);
+        half.set_pixel(0, 0, [200, 100, 0, 255]);
+        half.set_pixel(1, 0, [200, 100, 0, 128]);
+        let compositor = Compositor::new(3, 1)
+            .background(Rgb::new(0, 0, 100))
+            .layer(half.clone())
+            .layer(half.with_opacity(0.5).with_offset(1, 0));
+        assert_eq!(
+            compositor.compose(),
+            [
+                Rgb::new(200, 100, 0),
+                Rgb::new(150, 75, 25),
+                Rgb::new(50, 25, 75),
+            ]
+        );
+    }
+
+    #[test]
+    fn add_and_multiply() {
+        let compositor = Compositor::new(1, 1)
+            .background(Rgb::new(100, 200, 255))
+            .layer(opaque(1, 1, [100, 100, 100]).with_blend_mode(BlendMode::Add));
+        assert_eq!(compositor.compose(), [Rgb::new(200, 255, 255)]);
+
+        let compositor = Compositor::new(1, 1)
+            .background(Rgb::new(100, 200, 255))
+            .layer(opaque(1, 1, [255, 0, 51]).with_blend_mode(BlendMode::Multiply));
+        assert_eq!(compositor.compose(), [Rgb::new(100, 0, 51)]);
+    }
+
+    #[test]
+    fn order_and_visibility() {
+        let mut compositor = Compositor::new(1, 1)
+            .layer(opaque(1, 1, [1, 1, 1]))
+            .layer(opaque(1, 1, [2, 2, 2]));
+        assert_eq!(compositor.compose(), [Rgb::new(2, 2, 2)]);
+        compositor.layers_mut()[1].set_visible(false);
+        assert_eq!(compositor.compose(), [Rgb::new(1, 1, 1)]);
+        compositor.layers_mut().swap(0, 1);
+        assert_eq!(compositor.compose(), [Rgb::new(1, 1, 1)]);
+    }
+
+    #[test]
+    fn offset_outside_is_clipped() {
+        let compositor = Compositor::new(2, 2).layer(opaque(2, 2, [9, 9, 9]).with_offset(-1, 1));
+        assert_eq!(
+            compositor.compose(),
+            [Rgb::BLACK, Rgb::BLACK, Rgb::new(9, 9, 9), Rgb::BLACK,]
+        );
+    }
+
+    #[test]
+    fn copy_keyed_makes_key_transparent() {
+        let mut layer = Layer::new(2, 1);
+        layer.copy_keyed(&[[0, 0, 0], [5, 6, 7]], [0, 0, 0]);
+        assert_eq!(layer.pixels(), [[0, 0, 0, 0], [5, 6, 7, 255]]);
+        layer.copy_from(&[[0, 0, 0]]);
+        assert_eq!(layer.pixels(), [[0, 0, 0, 255], [5, 6, 7, 255]]);
+    }
+}
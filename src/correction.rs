@@ -0,0 +1,172 @@
+//! Color correction applied by the clients to every color before it is sent.
+//!
+//! WS2812 LEDs respond linearly to their input which looks washed out to the human eye.
+//! A gamma of around 2.2 compensates for that.
+
+use crate::color::Rgb;
+use crate::protocol::Message;
+
+/// Gamma, brightness and white balance combined into one lookup table per channel.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Correction {
+    gamma: f32,
+    brightness: f32,
+    white_balance: [f32; 3],
+    tables: [[u8; 256]; 3],
+}
+
+impl Default for Correction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Correction {
+    /// Smallest accepted [`gamma`](Self::gamma).
+    pub const MIN_GAMMA: f32 = 0.1;
+
+    /// No correction at all. Adapt it with the other methods.
+    pub const fn new() -> Self {
+        let mut identity = [0; 256];
+        let mut index = 0;
+        while index < identity.len() {
+            #[expect(clippy::cast_possible_truncation)]
+            {
+                identity[index] = index as u8;
+            }
+            index += 1;
+        }
+        Self {
+            gamma: 1.0,
+            brightness: 1.0,
+            white_balance: [1.0; 3],
+            tables: [identity; 3],
+        }
+    }
+
+    /// Exponent applied to every channel. 1.0 disables it, WS2812 look good with around 2.2.
+    ///
+    /// Values below [`MIN_GAMMA`](Self::MIN_GAMMA) are raised to it as a gamma of 0 would turn black into full brightness.
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.gamma = gamma.max(Self::MIN_GAMMA);
+        self.recalculate();
+        self
+    }
+
+    /// Scale every channel after the gamma correction. 1.0 is full brightness, 0.0 is off.
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.clamp(0.0, 1.0);
+        self.recalculate();
+        self
+    }
+
+    /// Scale each channel individually to compensate a tint of the LEDs. Each factor is between 0.0 and 1.0.
+    pub fn white_balance(mut self, red: f32, green: f32, blue: f32) -> Self {
+        self.white_balance = [red, green, blue].map(|factor| factor.clamp(0.0, 1.0));
+        self.recalculate();
+        self
+    }
+
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn recalculate(&mut self) {
+        for channel in 0..3 {
+            for input in 0_u8..=255 {
+                self.tables[channel][usize::from(input)] = self.exact(channel, input).round() as u8;
+            }
+        }
+    }
+
+    /// Output of the channel (0 red, 1 green, 2 blue) before it is rounded to the 8 bits sent to the LEDs.
+    pub(crate) fn exact(&self, channel: usize, input: u8) -> f32 {
+        let linear = (f32::from(input) / 255.0).powf(self.gamma);
+        (linear * self.brightness * self.white_balance[channel] * 255.0).clamp(0.0, 255.0)
+    }
+
+    /// Lookup table of the channel (0 red, 1 green, 2 blue).
+    #[cfg(feature = "image")]
+    pub(crate) const fn table(&self, channel: usize) -> &[u8; 256] {
+        &self.tables[channel]
+    }
+
+    #[must_use]
+    pub const fn apply(&self, color: Rgb) -> Rgb {
+        let [red, green, blue] = &self.tables;
+        Rgb::new(
+            red[color.red as usize],
+            green[color.green as usize],
+            blue[color.blue as usize],
+        )
+    }
+
+    /// Copy of the message with every color corrected.
+    #[must_use]
+    pub fn apply_message(&self, message: &Message) -> Message {
+        let mut message = message.clone();
+        message.map_colors(|color| self.apply(color));
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let correction = Correction::new();
+        for value in 0..=255 {
+            let color = Rgb::new(value, value / 2, 255 - value);
+            assert_eq!(correction.apply(color), color);
+        }
+        assert_eq!(correction, Correction::new().gamma(1.0).brightness(2.0));
+    }
+
+    #[test]
+    fn gamma_keeps_extremes() {
+        let correction = Correction::new().gamma(2.2);
+        assert_eq!(correction.apply(Rgb::BLACK), Rgb::BLACK);
+        assert_eq!(correction.apply(Rgb::WHITE), Rgb::WHITE);
+        assert_eq!(correction.apply(Rgb::new(128, 64, 1)), Rgb::new(56, 12, 0));
+    }
+
+    #[test]
+    fn zero_gamma_keeps_black() {
+        for gamma in [0.0, -1.0, f32::NAN] {
+            let correction = Correction::new().gamma(gamma);
+            assert_eq!(correction.apply(Rgb::BLACK), Rgb::BLACK);
+            assert_eq!(correction.apply(Rgb::WHITE), Rgb::WHITE);
+        }
+    }
+
+    #[test]
+    fn brightness_and_white_balance() {
+        let correction = Correction::new()
+            .brightness(0.5)
+            .white_balance(1.0, 0.8, 0.5);
+        assert_eq!(correction.apply(Rgb::WHITE), Rgb::new(128, 102, 64));
+        assert_eq!(correction.apply(Rgb::BLACK), Rgb::BLACK);
+    }
+
+    #[test]
+    fn message_colors() {
+        let correction = Correction::new().brightness(0.5);
+        let message = Message::Contiguous {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            colors: vec![255, 0, 100, 2, 4, 6],
+        };
+        assert_eq!(
+            correction.apply_message(&message),
+            Message::Contiguous {
+                x: 0,
+                y: 0,
+                width: 2,
+                height: 1,
+                colors: vec![128, 0, 50, 1, 2, 3],
+            }
+        );
+    }
+}
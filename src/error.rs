@@ -0,0 +1,136 @@
+use core::fmt;
+
+/// Everything that can go wrong when talking to an espPixelmatrix.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The connection failed. Timeouts are reported with [`std::io::ErrorKind::TimedOut`].
+    Io(std::io::Error),
+    /// The server speaks a protocol version other than [`PROTOCOL_VERSION`](crate::protocol::PROTOCOL_VERSION).
+    UnsupportedProtocolVersion(u8),
+    /// A byte stream contained an unknown [`Command`](crate::protocol::Command).
+    UnknownCommand(u8),
+    /// The area does not fit on the display.
+    ///
+    /// Coordinates are wider than the protocol to also describe areas of a [tiled](crate::tiling) display.
+    OutOfBounds {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    },
+    /// The amount of color bytes does not match the area.
+    ColorBufferLength { expected: usize, actual: usize },
+    /// The content like a [`Framebuffer`](crate::framebuffer::Framebuffer), an animation
+    /// or a [`Compositor`](crate::compositor::Compositor) has a different size than the display.
+    SizeMismatch {
+        display: (u8, u8),
+        content: (u8, u8),
+    },
+    /// Another thread panicked while using the connection.
+    Poisoned,
+    /// Two [`Tile`](crate::tiling::Tile)s share the same position within the grid.
+    DuplicateTile { column: u8, row: u8 },
+    /// The text is neither a `#rrggbb` hex color nor a named CSS color.
+    InvalidColor(String),
+    /// The image could not be loaded.
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+}
+
+impl Error {
+    /// Whether the error was caused by an elapsed timeout.
+    #[must_use]
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Io(error) if error.kind() == std::io::ErrorKind::TimedOut)
+    }
+
+    /// Whether the error was caused by the connection and might be resolved by reconnecting.
+    #[must_use]
+    pub const fn is_connection_error(&self) -> bool {
+        matches!(self, Self::Io(_))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => error.fmt(fmt),
+            Self::UnsupportedProtocolVersion(version) => write!(
+                fmt,
+                "Protocol version is not {} but {version}",
+                crate::protocol::PROTOCOL_VERSION
+            ),
+            Self::UnknownCommand(command) => write!(fmt, "unknown command {command}"),
+            Self::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(fmt, "area {width}x{height} at {x} {y} too big for display"),
+            Self::ColorBufferLength { expected, actual } => write!(
+                fmt,
+                "colors is wrong length: expected {expected} bytes but got {actual}"
+            ),
+            Self::SizeMismatch {
+                display: (display_width, display_height),
+                content: (content_width, content_height),
+            } => write!(
+                fmt,
+                "content size {content_width}x{content_height} does not match display size {display_width}x{display_height}"
+            ),
+            Self::Poisoned => fmt.write_str("Mutex poisoned"),
+            Self::DuplicateTile { column, row } => {
+                write!(fmt, "more than one tile at column {column} row {row}")
+            }
+            Self::InvalidColor(text) => write!(fmt, "invalid color {text:?}"),
+            #[cfg(feature = "image")]
+            Self::Image(error) => error.fmt(fmt),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            #[cfg(feature = "image")]
+            Self::Image(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Returned by the clients once an earlier write failed and the connection was closed.
+#[cfg(any(feature = "sync", feature = "tokio"))]
+pub(crate) fn broken_connection() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::NotConnected,
+        "connection closed after a failed write",
+    )
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(error) => error,
+            Error::UnsupportedProtocolVersion(_) | Error::UnknownCommand(_) => {
+                Self::new(std::io::ErrorKind::InvalidData, error)
+            }
+            _ => Self::other(error),
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Tiny bitmap fonts for displays with only a few rows and laying out text with them.
+//!
+//! The clients draw the text with `draw_text` of their `Canvas`.
+//! Every font fits on a display with 8 rows including the dots of the umlauts.
+
+use crate::color::Rgb;
+
+mod mono_3x5;
+mod mono_4x6;
+mod proportional_5;
+
+/// Capitals in 3x5 pixels. Lowercase letters are shown as capitals.
+pub const FONT_3X5: Font = Font {
+    ascent: 1,
+    cap_height: 5,
+    descent: 0,
+    spacing: 1,
+    glyphs: mono_3x5::GLYPHS,
+    kerning: &[],
+};
+
+/// Capitals in 4x6 pixels with distinct digits. Lowercase letters are shown as capitals.
+pub const FONT_4X6: Font = Font {
+    ascent: 1,
+    cap_height: 6,
+    descent: 0,
+    spacing: 1,
+    glyphs: mono_4x6::GLYPHS,
+    kerning: &[],
+};
+
+/// Capitals of 5 pixels with lowercase letters and characters of different widths.
+pub const FONT_PROPORTIONAL_5: Font = Font {
+    ascent: 1,
+    cap_height: 5,
+    descent: 1,
+    spacing: 1,
+    glyphs: proportional_5::GLYPHS,
+    kerning: proportional_5::KERNING,
+};
+
+/// One character of a [`Font`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    pub character: char,
+    /// Row of the line where the first row of the glyph is drawn. 0 is the top of the [ascent](Font::ascent).
+    pub top: u8,
+    /// Row by row from the top, `#` is set and every other character is not.
+    pub rows: &'static [&'static str],
+}
+
+impl Glyph {
+    #[must_use]
+    pub const fn new(character: char, top: u8, rows: &'static [&'static str]) -> Self {
+        Self {
+            character,
+            top,
+            rows,
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u8 {
+        let width = self.rows.first().map_or(0, |row| row.chars().count());
+        u8::try_from(width).unwrap_or(u8::MAX)
+    }
+
+    /// Position of every set pixel relative to the left of the glyph and the top of the line.
+    fn pixels(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        (self.top..).zip(self.rows).flat_map(|(y, row)| {
+            (0..)
+                .zip(row.chars())
+                .filter(|(_x, pixel)| *pixel == '#')
+                .map(move |(x, _pixel)| (x, y))
+        })
+    }
+}
+
+/// Bitmap font made for displays with only a few rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Font {
+    /// Rows above the capitals, used by the dots of umlauts.
+    pub ascent: u8,
+    pub cap_height: u8,
+    /// Rows below the baseline, used by letters like g or p.
+    pub descent: u8,
+    /// Pixels between two characters.
+    pub spacing: u8,
+    pub glyphs: &'static [Glyph],
+    /// Pairs of characters with their change of the spacing between them.
+    pub kerning: &'static [(char, char, i8)],
+}
+
+impl Font {
+    #[must_use]
+    pub const fn line_height(&self) -> u8 {
+        self.ascent + self.cap_height + self.descent
+    }
+
+    /// The glyph shown for the character.
+    ///
+    /// Characters missing in the font are shown by their capital or otherwise by `?`.
+    #[must_use]
+    pub fn glyph(&self, character: char) -> Option<&Glyph> {
+        let find = |character| {
+            self.glyphs
+                .iter()
+                .find(|glyph| glyph.character == character)
+        };
+        find(character)
+            .or_else(|| character.to_uppercase().next().and_then(find))
+            .or_else(|| find('?'))
+    }
+
+    /// Change of the spacing between the two characters.
+    #[must_use]
+    pub fn kerning(&self, left: char, right: char) -> i8 {
+        self.kerning
+            .iter()
+            .find(|(kern_left, kern_right, _)| *kern_left == left && *kern_right == right)
+            .map_or(0, |(.., kerning)| *kerning)
+    }
+
+    /// Width of the text in pixels when drawn in a single line.
+    #[must_use]
+    pub fn text_width(&self, text: &str) -> u32 {
+        self.place(text).last().map_or(0, |(x, glyph)| {
+            u32::try_from(x + i32::from(glyph.width())).unwrap_or(0)
+        })
+    }
+
+    /// Every glyph of the text with its position from the left.
+    fn place<'font>(&'font self, text: &str) -> impl Iterator<Item = (i32, &'font Glyph)> {
+        let mut x = 0;
+        let mut previous: Option<(char, u8)> = None;
+        text.chars().filter_map(move |character| {
+            let glyph = self.glyph(character)?;
+            if let Some((previous, width)) = previous {
+                x += i32::from(width)
+                    + i32::from(self.spacing)
+                    + i32::from(self.kerning(previous, character));
+            }
+            previous = Some((character, glyph.width()));
+            Some((x, glyph))
+        })
+    }
+
+    /// Split the text into lines no wider than `max_width`. Words are only split when they are too long on their own.
+    fn wrap<'text>(&self, paragraph: &'text str, max_width: u32) -> Vec<&'text str> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+        for (index, _) in paragraph.match_indices(' ').chain([(paragraph.len(), "")]) {
+            if end > start && self.text_width(&paragraph[start..index]) > max_width {
+                lines.push(&paragraph[start..end]);
+                start = end + 1;
+            }
+            end = index;
+            while self.text_width(&paragraph[start..end]) > max_width {
+                let split = self.fitting(&paragraph[start..end], max_width);
+                lines.push(&paragraph[start..start + split]);
+                start += split;
+            }
+        }
+        lines.push(&paragraph[start..]);
+        lines
+    }
+
+    /// Length in bytes of the longest beginning of the text which fits. At least one character.
+    fn fitting(&self, text: &str, max_width: u32) -> usize {
+        let mut fitting = text.chars().next().map_or(0, char::len_utf8);
+        for (index, character) in text.char_indices().skip(1) {
+            let end = index + character.len_utf8();
+            if self.text_width(&text[..end]) > max_width {
+                break;
+            }
+            fitting = end;
+        }
+        fitting
+    }
+}
+
+/// Horizontal position of the lines within the text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// How text is laid out and colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct TextStyle {
+    font: &'static Font,
+    color: Rgb,
+    background: Option<Rgb>,
+    alignment: Alignment,
+    max_width: Option<u32>,
+    line_spacing: u8,
+}
+
+impl TextStyle {
+    /// Left aligned without wrapping on a transparent background.
+    pub const fn new(font: &'static Font, color: Rgb) -> Self {
+        Self {
+            font,
+            color,
+            background: None,
+            alignment: Alignment::Left,
+            max_width: None,
+            line_spacing: 1,
+        }
+    }
+
+    pub const fn color(mut self, color: Rgb) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Fill the whole area of the text. Without a background only the pixels of the characters are drawn.
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Align the lines within the widest line or the width to wrap at.
+    pub const fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Break lines between words so they are not wider than the given width.
+    pub const fn wrap(mut self, max_width: u32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Empty rows between two lines.
+    pub const fn line_spacing(mut self, line_spacing: u8) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Lay out the text. Lines are broken at `\n` and when wrapping.
+    #[must_use]
+    pub fn layout(&self, text: &str) -> TextLayout {
+        let font = self.font;
+        let lines = text
+            .split('\n')
+            .flat_map(|paragraph| {
+                self.max_width.map_or_else(
+                    || vec![paragraph],
+                    |max_width| font.wrap(paragraph, max_width),
+                )
+            })
+            .map(|line| (line, font.text_width(line)))
+            .collect::<Vec<_>>();
+        let width = self.max_width.unwrap_or_else(|| {
+            lines
+                .iter()
+                .map(|(_, line_width)| *line_width)
+                .max()
+                .unwrap_or(0)
+        });
+        let line_height = u32::from(font.line_height());
+        let line_count = u32::try_from(lines.len()).unwrap_or(u32::MAX);
+        let height = line_count.saturating_mul(line_height)
+            + line_count.saturating_sub(1) * u32::from(self.line_spacing);
+
+        let mut layout = TextLayout {
+            width,
+            height,
+            pixels: vec![self.background; (width as usize) * (height as usize)],
+        };
+        for ((line, line_width), top) in lines.iter().zip(
+            (0_u32..).step_by(usize::from(font.line_height()) + usize::from(self.line_spacing)),
+        ) {
+            let left = match self.alignment {
+                Alignment::Left => 0,
+                Alignment::Center => width.saturating_sub(*line_width) / 2,
+                Alignment::Right => width.saturating_sub(*line_width),
+            };
+            for (x, glyph) in font.place(line) {
+                for (glyph_x, glyph_y) in glyph.pixels() {
+                    let x = i64::from(left) + i64::from(x) + i64::from(glyph_x);
+                    let y = i64::from(top) + i64::from(glyph_y);
+                    layout.set(x, y, self.color);
+                }
+            }
+        }
+        layout
+    }
+}
+
+/// Colors of laid out text, see [`TextStyle::layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLayout {
+    width: u32,
+    height: u32,
+    /// Row by row starting in the top left. `None` is transparent.
+    pixels: Vec<Option<Rgb>>,
+}
+
+impl TextLayout {
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Row by row starting in the top left. `None` is transparent.
+    #[must_use]
+    pub fn pixels(&self) -> &[Option<Rgb>] {
+        &self.pixels
+    }
+
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Rgb> {
+        if x >= self.width {
+            return None;
+        }
+        let index = usize::try_from(u64::from(y) * u64::from(self.width) + u64::from(x)).ok()?;
+        self.pixels.get(index).copied().flatten()
+    }
+
+    /// Every pixel has a color, so the whole area can be sent at once.
+    #[must_use]
+    pub fn is_opaque(&self) -> bool {
+        self.pixels.iter().all(Option::is_some)
+    }
+
+    /// Horizontal runs of pixels in the same color as x, y, length and color.
+    pub fn runs(&self) -> impl Iterator<Item = (u32, u32, u32, Rgb)> + '_ {
+        self.pixels
+            .chunks_exact((self.width as usize).max(1))
+            .zip(0..)
+            .flat_map(|(row, y)| {
+                let mut runs = Vec::new();
+                let mut start = 0;
+                for (x, color) in (0..).zip(row) {
+                    let next = row.get(x as usize + 1).copied().flatten();
+                    if let Some(color) = color.filter(|color| next != Some(*color)) {
+                        runs.push((start, y, x + 1 - start, color));
+                    }
+                    if next != *color {
+                        start = x + 1;
+                    }
+                }
+                runs
+            })
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: Rgb) {
+        let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+            return;
+        };
+        if x < self.width && y < self.height {
+            self.pixels[y as usize * self.width as usize + x as usize] = Some(color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONTS: [&Font; 3] = [&FONT_3X5, &FONT_4X6, &FONT_PROPORTIONAL_5];
+
+    /// Rows of the layout with `#` for the text color and `.` for everything else
+    fn render(layout: &TextLayout) -> Vec<String> {
+        (0..layout.height())
+            .map(|y| {
+                (0..layout.width())
+                    .map(|x| {
+                        if layout.pixel(x, y) == Some(Rgb::WHITE) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn glyphs_fit_into_the_line() {
+        for font in FONTS {
+            for glyph in font.glyphs {
+                let width = glyph.width();
+                assert!(width > 0, "{:?}", glyph.character);
+                assert!(
+                    glyph
+                        .rows
+                        .iter()
+                        .all(|row| row.chars().count() == usize::from(width)),
+                    "{:?}",
+                    glyph.character
+                );
+                assert!(
+                    usize::from(glyph.top) + glyph.rows.len() <= usize::from(font.line_height()),
+                    "{:?}",
+                    glyph.character
+                );
+                let duplicates = font
+                    .glyphs
+                    .iter()
+                    .filter(|other| other.character == glyph.character)
+                    .count();
+                assert_eq!(duplicates, 1, "{:?}", glyph.character);
+            }
+            assert!(font.line_height() <= 8);
+        }
+        for (font, width) in [(&FONT_3X5, 3), (&FONT_4X6, 4)] {
+            assert!(font.glyphs.iter().all(|glyph| glyph.width() == width));
+        }
+    }
+
+    #[test]
+    fn fallback() {
+        assert_eq!(FONT_3X5.glyph('ä'), FONT_3X5.glyph('Ä'));
+        assert_eq!(FONT_3X5.glyph('€'), FONT_3X5.glyph('?'));
+        assert_ne!(
+            FONT_PROPORTIONAL_5.glyph('a'),
+            FONT_PROPORTIONAL_5.glyph('A')
+        );
+        for font in FONTS {
+            for character in "0123456789ÄÖÜäöüß".chars() {
+                assert_ne!(font.glyph(character), font.glyph('?'), "{character}");
+            }
+        }
+    }
+
+    #[test]
+    fn kerning() {
+        assert_eq!(FONT_PROPORTIONAL_5.text_width("Tx"), 7);
+        assert_eq!(FONT_PROPORTIONAL_5.text_width("To"), 6);
+        assert_eq!(FONT_PROPORTIONAL_5.text_width("Mil"), 5 + 1 + 1 + 1 + 2);
+        assert_eq!(FONT_3X5.text_width("To"), 7);
+        assert_eq!(FONT_3X5.text_width(""), 0);
+    }
+
+    #[test]
+    fn umlaut_uses_ascent() {
+        let layout = TextStyle::new(&FONT_3X5, Rgb::WHITE).layout("Ä1");
+        assert_eq!(
+            render(&layout),
+            [
+                "#.#....", //
+                ".#...#.", //
+                "#.#.##.", //
+                "###..#.", //
+                "#.#..#.", //
+                "#.#.###",
+            ]
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        let style = TextStyle::new(&FONT_3X5, Rgb::WHITE)
+            .wrap(9)
+            .line_spacing(0);
+        let left = style.layout("-\n--");
+        assert_eq!(render(&left)[3], "###......");
+        assert_eq!(render(&left)[9], "###.###..");
+        let center = style.alignment(Alignment::Center).layout("-");
+        assert_eq!(render(&center)[3], "...###...");
+        let right = style.alignment(Alignment::Right).layout("-");
+        assert_eq!(render(&right)[3], "......###");
+    }
+
+    #[test]
+    fn wrapping() {
+        let style = TextStyle::new(&FONT_3X5, Rgb::WHITE).wrap(7);
+        assert_eq!(FONT_3X5.wrap("A B CD", 11), ["A B", "CD"]);
+        // Too long words are split
+        assert_eq!(FONT_3X5.wrap("ABCDE F", 11), ["ABC", "DE", "F"]);
+        assert_eq!(FONT_3X5.wrap("", 7), [""]);
+        let layout = style.layout("AB CD\nE");
+        assert_eq!((layout.width(), layout.height()), (7, 6 * 3 + 2));
+    }
+
+    #[test]
+    fn runs_and_background() {
+        let layout = TextStyle::new(&FONT_3X5, Rgb::WHITE).layout("-");
+        assert!(!layout.is_opaque());
+        assert_eq!(layout.runs().collect::<Vec<_>>(), [(0, 3, 3, Rgb::WHITE)]);
+
+        let layout = TextStyle::new(&FONT_3X5, Rgb::WHITE)
+            .background(Rgb::BLUE)
+            .layout(".");
+        assert!(layout.is_opaque());
+        assert_eq!(layout.pixel(0, 5), Some(Rgb::BLUE));
+        assert_eq!(layout.pixel(1, 5), Some(Rgb::WHITE));
+        assert_eq!(
+            layout.runs().filter(|run| run.1 == 5).collect::<Vec<_>>(),
+            [
+                (0, 5, 1, Rgb::BLUE),
+                (1, 5, 1, Rgb::WHITE),
+                (2, 5, 1, Rgb::BLUE)
+            ]
+        );
+    }
+}
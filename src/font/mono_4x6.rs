@@ -0,0 +1,76 @@
+use super::Glyph;
+
+/// Lowercase letters are shown as capitals.
+/// The zero is slashed to tell it apart from the O.
+pub const GLYPHS: &[Glyph] = &[
+    Glyph::new(' ', 1, &["...."]),
+    Glyph::new('0', 1, &[".##.", "#..#", "#.##", "##.#", "#..#", ".##."]),
+    Glyph::new('1', 1, &["..#.", ".##.", "..#.", "..#.", "..#.", ".###"]),
+    Glyph::new('2', 1, &[".##.", "#..#", "...#", "..#.", ".#..", "####"]),
+    Glyph::new('3', 1, &["###.", "...#", ".##.", "...#", "...#", "###."]),
+    Glyph::new('4', 1, &["..#.", ".##.", "#.#.", "####", "..#.", "..#."]),
+    Glyph::new('5', 1, &["####", "#...", "###.", "...#", "...#", "###."]),
+    Glyph::new('6', 1, &[".##.", "#...", "###.", "#..#", "#..#", ".##."]),
+    Glyph::new('7', 1, &["####", "...#", "..#.", ".#..", ".#..", ".#.."]),
+    Glyph::new('8', 1, &[".##.", "#..#", ".##.", "#..#", "#..#", ".##."]),
+    Glyph::new('9', 1, &[".##.", "#..#", "#..#", ".###", "...#", ".##."]),
+    Glyph::new('A', 1, &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    Glyph::new('B', 1, &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    Glyph::new('C', 1, &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    Glyph::new('D', 1, &["###.", "#..#", "#..#", "#..#", "#..#", "###."]),
+    Glyph::new('E', 1, &["####", "#...", "###.", "#...", "#...", "####"]),
+    Glyph::new('F', 1, &["####", "#...", "###.", "#...", "#...", "#..."]),
+    Glyph::new('G', 1, &[".##.", "#...", "#...", "#.##", "#..#", ".###"]),
+    Glyph::new('H', 1, &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    Glyph::new('I', 1, &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    Glyph::new('J', 1, &["...#", "...#", "...#", "...#", "#..#", ".##."]),
+    Glyph::new('K', 1, &["#..#", "#.#.", "##..", "#.#.", "#..#", "#..#"]),
+    Glyph::new('L', 1, &["#...", "#...", "#...", "#...", "#...", "####"]),
+    Glyph::new('M', 1, &["#..#", "####", "####", "#..#", "#..#", "#..#"]),
+    Glyph::new('N', 1, &["#..#", "##.#", "#.##", "#..#", "#..#", "#..#"]),
+    Glyph::new('O', 1, &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    Glyph::new('P', 1, &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    Glyph::new('Q', 1, &[".##.", "#..#", "#..#", "#..#", "#.#.", ".#.#"]),
+    Glyph::new('R', 1, &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    Glyph::new('S', 1, &[".###", "#...", ".##.", "...#", "...#", "###."]),
+    Glyph::new('T', 1, &["###.", ".#..", ".#..", ".#..", ".#..", ".#.."]),
+    Glyph::new('U', 1, &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    Glyph::new('V', 1, &["#..#", "#..#", "#..#", "#..#", ".##.", ".##."]),
+    Glyph::new('W', 1, &["#..#", "#..#", "#..#", "####", "####", "#..#"]),
+    Glyph::new('X', 1, &["#..#", "#..#", ".##.", ".##.", "#..#", "#..#"]),
+    Glyph::new('Y', 1, &["#.#.", "#.#.", ".#..", ".#..", ".#..", ".#.."]),
+    Glyph::new('Z', 1, &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    Glyph::new(
+        'Ä',
+        0,
+        &["#..#", ".##.", "#..#", "#..#", "####", "#..#", "#..#"],
+    ),
+    Glyph::new(
+        'Ö',
+        0,
+        &["#..#", ".##.", "#..#", "#..#", "#..#", "#..#", ".##."],
+    ),
+    Glyph::new('Ü', 1, &["#..#", "....", "#..#", "#..#", "#..#", ".##."]),
+    Glyph::new('ß', 1, &[".##.", "#..#", "#.#.", "#..#", "#..#", "#.#."]),
+    Glyph::new('.', 6, &[".#.."]),
+    Glyph::new(',', 5, &[".#..", "#..."]),
+    Glyph::new(':', 2, &[".#..", "....", "....", ".#.."]),
+    Glyph::new(';', 2, &[".#..", "....", "....", ".#..", "#..."]),
+    Glyph::new('!', 1, &[".#..", ".#..", ".#..", ".#..", "....", ".#.."]),
+    Glyph::new('?', 1, &[".##.", "#..#", "..#.", ".#..", "....", ".#.."]),
+    Glyph::new('-', 3, &["####"]),
+    Glyph::new('+', 2, &[".#..", "###.", ".#.."]),
+    Glyph::new('=', 2, &["####", "....", "####"]),
+    Glyph::new('/', 1, &["...#", "..#.", "..#.", ".#..", ".#..", "#..."]),
+    Glyph::new('%', 1, &["#..#", "...#", "..#.", ".#..", "#...", "#..#"]),
+    Glyph::new('\'', 1, &[".#..", ".#.."]),
+    Glyph::new('"', 1, &["#.#.", "#.#."]),
+    Glyph::new('(', 1, &["..#.", ".#..", ".#..", ".#..", ".#..", "..#."]),
+    Glyph::new(')', 1, &[".#..", "..#.", "..#.", "..#.", "..#.", ".#.."]),
+    Glyph::new('°', 1, &[".#..", "#.#.", ".#.."]),
+    Glyph::new('_', 6, &["####"]),
+    Glyph::new('*', 2, &["#.#.", ".#..", "#.#."]),
+    Glyph::new('#', 1, &[".#.#", "####", ".#.#", "#.#.", "####", "#.#."]),
+    Glyph::new('<', 1, &["...#", "..#.", ".#..", "..#.", "...#"]),
+    Glyph::new('>', 1, &["#...", ".#..", "..#.", ".#..", "#..."]),
+];
@@ -0,0 +1,123 @@
+use super::Glyph;
+
+/// Capitals are 5 rows high, lowercase letters 4 rows with one row below the baseline.
+/// Digits all share the same width so numbers do not jump around when they change.
+pub const GLYPHS: &[Glyph] = &[
+    Glyph::new(' ', 1, &[".."]),
+    Glyph::new('0', 1, &["###", "#.#", "#.#", "#.#", "###"]),
+    Glyph::new('1', 1, &[".#.", "##.", ".#.", ".#.", "###"]),
+    Glyph::new('2', 1, &["##.", "..#", ".#.", "#..", "###"]),
+    Glyph::new('3', 1, &["##.", "..#", ".#.", "..#", "##."]),
+    Glyph::new('4', 1, &["#.#", "#.#", "###", "..#", "..#"]),
+    Glyph::new('5', 1, &["###", "#..", "##.", "..#", "##."]),
+    Glyph::new('6', 1, &[".##", "#..", "###", "#.#", "###"]),
+    Glyph::new('7', 1, &["###", "..#", ".#.", ".#.", ".#."]),
+    Glyph::new('8', 1, &["###", "#.#", "###", "#.#", "###"]),
+    Glyph::new('9', 1, &["###", "#.#", "###", "..#", "##."]),
+    Glyph::new('A', 1, &[".#.", "#.#", "###", "#.#", "#.#"]),
+    Glyph::new('B', 1, &["##.", "#.#", "##.", "#.#", "##."]),
+    Glyph::new('C', 1, &[".##", "#..", "#..", "#..", ".##"]),
+    Glyph::new('D', 1, &["##.", "#.#", "#.#", "#.#", "##."]),
+    Glyph::new('E', 1, &["###", "#..", "##.", "#..", "###"]),
+    Glyph::new('F', 1, &["###", "#..", "##.", "#..", "#.."]),
+    Glyph::new('G', 1, &[".##.", "#...", "#.##", "#..#", ".##."]),
+    Glyph::new('H', 1, &["#.#", "#.#", "###", "#.#", "#.#"]),
+    Glyph::new('I', 1, &["###", ".#.", ".#.", ".#.", "###"]),
+    Glyph::new('J', 1, &["..#", "..#", "..#", "#.#", ".#."]),
+    Glyph::new('K', 1, &["#..#", "#.#.", "##..", "#.#.", "#..#"]),
+    Glyph::new('L', 1, &["#..", "#..", "#..", "#..", "###"]),
+    Glyph::new('M', 1, &["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    Glyph::new('N', 1, &["#..#", "##.#", "#.##", "#..#", "#..#"]),
+    Glyph::new('O', 1, &[".##.", "#..#", "#..#", "#..#", ".##."]),
+    Glyph::new('P', 1, &["##.", "#.#", "##.", "#..", "#.."]),
+    Glyph::new('Q', 1, &[".##.", "#..#", "#..#", "#.#.", ".#.#"]),
+    Glyph::new('R', 1, &["##.", "#.#", "##.", "#.#", "#.#"]),
+    Glyph::new('S', 1, &[".##", "#..", ".#.", "..#", "##."]),
+    Glyph::new('T', 1, &["###", ".#.", ".#.", ".#.", ".#."]),
+    Glyph::new('U', 1, &["#.#", "#.#", "#.#", "#.#", "###"]),
+    Glyph::new('V', 1, &["#.#", "#.#", "#.#", "#.#", ".#."]),
+    Glyph::new('W', 1, &["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    Glyph::new('X', 1, &["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    Glyph::new('Y', 1, &["#.#", "#.#", ".#.", ".#.", ".#."]),
+    Glyph::new('Z', 1, &["###", "..#", ".#.", "#..", "###"]),
+    Glyph::new('Ä', 0, &["#.#", ".#.", "#.#", "###", "#.#", "#.#"]),
+    Glyph::new('Ö', 0, &["#..#", ".##.", "#..#", "#..#", "#..#", ".##."]),
+    Glyph::new('Ü', 0, &["#.#", "...", "#.#", "#.#", "#.#", "###"]),
+    Glyph::new('a', 2, &[".##", "#.#", "#.#", ".##"]),
+    Glyph::new('b', 1, &["#..", "##.", "#.#", "#.#", "##."]),
+    Glyph::new('c', 2, &[".##", "#..", "#..", ".##"]),
+    Glyph::new('d', 1, &["..#", ".##", "#.#", "#.#", ".##"]),
+    Glyph::new('e', 2, &[".#.", "###", "#..", ".##"]),
+    Glyph::new('f', 1, &["..#", ".#.", "###", ".#.", ".#."]),
+    Glyph::new('g', 2, &[".##", "#.#", ".##", "..#", "##."]),
+    Glyph::new('h', 1, &["#..", "##.", "#.#", "#.#", "#.#"]),
+    Glyph::new('i', 1, &["#", ".", "#", "#", "#"]),
+    Glyph::new('j', 1, &[".#", "..", ".#", ".#", ".#", "#."]),
+    Glyph::new('k', 1, &["#..", "#.#", "##.", "#.#", "#.#"]),
+    Glyph::new('l', 1, &["#.", "#.", "#.", "#.", ".#"]),
+    Glyph::new('m', 2, &["##.#.", "#.#.#", "#.#.#", "#.#.#"]),
+    Glyph::new('n', 2, &["##.", "#.#", "#.#", "#.#"]),
+    Glyph::new('o', 2, &[".#.", "#.#", "#.#", ".#."]),
+    Glyph::new('p', 2, &["##.", "#.#", "##.", "#..", "#.."]),
+    Glyph::new('q', 2, &[".##", "#.#", ".##", "..#", "..#"]),
+    Glyph::new('r', 2, &["#.#", "##.", "#..", "#.."]),
+    Glyph::new('s', 2, &[".##", "##.", "..#", "##."]),
+    Glyph::new('t', 1, &[".#.", "###", ".#.", ".#.", "..#"]),
+    Glyph::new('u', 2, &["#.#", "#.#", "#.#", ".##"]),
+    Glyph::new('v', 2, &["#.#", "#.#", "#.#", ".#."]),
+    Glyph::new('w', 2, &["#...#", "#...#", "#.#.#", ".#.#."]),
+    Glyph::new('x', 2, &["#.#", ".#.", ".#.", "#.#"]),
+    Glyph::new('y', 2, &["#.#", "#.#", ".##", "..#", "##."]),
+    Glyph::new('z', 2, &["###", "..#", "#..", "###"]),
+    Glyph::new('ä', 1, &["#.#", ".##", "#.#", "#.#", ".##"]),
+    Glyph::new('ö', 1, &["#.#", ".#.", "#.#", "#.#", ".#."]),
+    Glyph::new('ü', 0, &["#.#", "...", "#.#", "#.#", "#.#", ".##"]),
+    Glyph::new('ß', 1, &[".#.", "#.#", "##.", "#.#", "##.", "#.."]),
+    Glyph::new('.', 5, &["#"]),
+    Glyph::new(',', 5, &[".#", "#."]),
+    Glyph::new(':', 3, &["#", ".", "#"]),
+    Glyph::new(';', 3, &[".#", "..", ".#", "#."]),
+    Glyph::new('!', 1, &["#", "#", "#", ".", "#"]),
+    Glyph::new('?', 1, &["##.", "..#", ".#.", "...", ".#."]),
+    Glyph::new('-', 3, &["###"]),
+    Glyph::new('+', 2, &[".#.", "###", ".#."]),
+    Glyph::new('=', 2, &["###", "...", "###"]),
+    Glyph::new('/', 1, &["..#", "..#", ".#.", "#..", "#.."]),
+    Glyph::new('%', 1, &["#.#", "..#", ".#.", "#..", "#.#"]),
+    Glyph::new('\'', 1, &["#", "#"]),
+    Glyph::new('"', 1, &["#.#", "#.#"]),
+    Glyph::new('(', 1, &[".#", "#.", "#.", "#.", ".#"]),
+    Glyph::new(')', 1, &["#.", ".#", ".#", ".#", "#."]),
+    Glyph::new('°', 1, &[".#.", "#.#", ".#."]),
+    Glyph::new('_', 6, &["###"]),
+    Glyph::new('*', 1, &["#.#", ".#.", "#.#"]),
+    Glyph::new('#', 1, &[".#.#.", "#####", ".#.#.", "#####", ".#.#."]),
+    Glyph::new('<', 1, &["..#", ".#.", "#..", ".#.", "..#"]),
+    Glyph::new('>', 1, &["#..", ".#.", "..#", ".#.", "#.."]),
+];
+
+/// Pairs of characters which are moved closer together as their shapes leave a gap anyway.
+pub const KERNING: &[(char, char, i8)] = &[
+    ('F', ',', -1),
+    ('F', '.', -1),
+    ('L', '\'', -1),
+    ('L', 'T', -1),
+    ('L', 'V', -1),
+    ('L', 'Y', -1),
+    ('P', ',', -1),
+    ('P', '.', -1),
+    ('T', ',', -1),
+    ('T', '.', -1),
+    ('T', 'a', -1),
+    ('T', 'c', -1),
+    ('T', 'e', -1),
+    ('T', 'o', -1),
+    ('T', 's', -1),
+    ('T', 'u', -1),
+    ('V', ',', -1),
+    ('V', '.', -1),
+    ('Y', ',', -1),
+    ('Y', '.', -1),
+    ('r', ',', -1),
+    ('r', '.', -1),
+];
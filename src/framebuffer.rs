@@ -0,0 +1,298 @@
+use crate::planner;
+use crate::protocol::Message;
+
+/// Local mirror of the remote matrix.
+///
+/// Draw into it freely and present it via the client.
+/// Only the difference to the last presented frame is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: u8,
+    height: u8,
+    pixels: Vec<[u8; 3]>,
+    /// What the remote matrix currently shows. `None` when unknown.
+    presented: Option<Vec<[u8; 3]>>,
+}
+
+impl Framebuffer {
+    /// Create a black framebuffer.
+    ///
+    /// The content of the remote matrix is unknown so the first present sends everything.
+    #[must_use]
+    pub fn new(width: u8, height: u8) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 3]; usize::from(width) * usize::from(height)],
+            presented: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// All pixels, row by row starting in the top left.
+    #[must_use]
+    pub fn pixels(&self) -> &[[u8; 3]] {
+        &self.pixels
+    }
+
+    /// All pixels to be changed at once, row by row starting in the top left.
+    pub fn pixels_mut(&mut self) -> &mut [[u8; 3]] {
+        &mut self.pixels
+    }
+
+    const fn index(&self, x: u8, y: u8) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some((y as usize) * (self.width as usize) + (x as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Color of the given pixel or `None` when it is outside of the framebuffer.
+    #[must_use]
+    pub fn pixel(&self, x: u8, y: u8) -> Option<[u8; 3]> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Set one pixel to the given color. Pixels outside of the framebuffer are ignored.
+    pub fn set_pixel(&mut self, x: u8, y: u8, color: [u8; 3]) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    /// Fill the whole framebuffer with one color.
+    pub fn fill(&mut self, color: [u8; 3]) {
+        self.pixels.fill(color);
+    }
+
+    /// Fill the given rectangular area with one color. Parts outside of the framebuffer are ignored.
+    pub fn rectangle(&mut self, x: u8, y: u8, width: u8, height: u8, color: [u8; 3]) {
+        let x_end = x.saturating_add(width).min(self.width);
+        let y_end = y.saturating_add(height).min(self.height);
+        for y in y..y_end {
+            for x in x..x_end {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Forget what the remote matrix shows so the next present sends everything.
+    ///
+    /// Useful when something else drew on the matrix or the connection was reestablished.
+    pub fn invalidate(&mut self) {
+        self.presented = None;
+    }
+
+    /// Remember the current content as shown on the remote matrix.
+    ///
+    /// Called by the clients after successfully presenting the [`diff`](Self::diff).
+    pub fn mark_presented(&mut self) {
+        self.presented = Some(self.pixels.clone());
+    }
+
+    /// Messages needed to transition the remote matrix from the last presented frame to the current content.
+    ///
+    /// See the [`planner`] for how the messages are chosen.
+    #[must_use]
+    pub fn diff(&self) -> Vec<Message> {
+        let changed = self.presented.as_ref().map_or_else(
+            || vec![true; self.pixels.len()],
+            |presented| {
+                presented
+                    .iter()
+                    .zip(&self.pixels)
+                    .map(|(before, now)| before != now)
+                    .collect()
+            },
+        );
+        planner::plan(self.width, self.height, &self.pixels, &changed)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Framebuffer {
+    /// Send the changes since the last present to the tokio client and flush.
+    ///
+    /// Same as [`async_tokio::Client::present`](crate::async_tokio::Client::present).
+    ///
+    /// # Errors
+    /// Errors when the framebuffer size does not match the display or the commands could not be sent
+    pub async fn flush_to(
+        &mut self,
+        client: &crate::async_tokio::Client,
+    ) -> Result<(), crate::error::Error> {
+        client.present(self).await
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics {
+    use embedded_graphics::geometry::{Dimensions as _, Size};
+    use embedded_graphics::pixelcolor::RgbColor as _;
+    use embedded_graphics::primitives::Rectangle;
+
+    use crate::framebuffer::Framebuffer;
+
+    impl embedded_graphics::geometry::OriginDimensions for Framebuffer {
+        fn size(&self) -> Size {
+            Size::new(u32::from(self.width), u32::from(self.height))
+        }
+    }
+
+    #[expect(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    impl embedded_graphics::draw_target::DrawTarget for Framebuffer {
+        type Color = embedded_graphics::pixelcolor::Rgb888;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+        {
+            let bounding_box = self.bounding_box();
+            for embedded_graphics::Pixel(point, color) in pixels {
+                if bounding_box.contains(point) {
+                    self.set_pixel(
+                        point.x as u8,
+                        point.y as u8,
+                        [color.r(), color.g(), color.b()],
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            let drawable_area = area.intersection(&self.bounding_box());
+            if drawable_area.is_zero_sized() {
+                return Ok(());
+            }
+            self.rectangle(
+                drawable_area.top_left.x as u8,
+                drawable_area.top_left.y as u8,
+                drawable_area.size.width as u8,
+                drawable_area.size.height as u8,
+                [color.r(), color.g(), color.b()],
+            );
+            Ok(())
+        }
+
+        fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+            self.fill([color.r(), color.g(), color.b()]);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_remote_sends_everything() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.fill([1, 2, 3]);
+        assert_eq!(
+            framebuffer.diff(),
+            [Message::Fill {
+                red: 1,
+                green: 2,
+                blue: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn nothing_changed() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.set_pixel(1, 1, [255, 0, 0]);
+        framebuffer.mark_presented();
+        assert_eq!(framebuffer.diff(), []);
+    }
+
+    #[test]
+    fn single_pixel() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.mark_presented();
+        framebuffer.set_pixel(1, 1, [255, 0, 0]);
+        assert_eq!(
+            framebuffer.diff(),
+            [Message::Pixel {
+                x: 1,
+                y: 1,
+                red: 255,
+                green: 0,
+                blue: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn runs_with_small_gaps_are_merged() {
+        let mut framebuffer = Framebuffer::new(8, 1);
+        framebuffer.mark_presented();
+        framebuffer.set_pixel(1, 0, [1, 1, 1]);
+        framebuffer.set_pixel(3, 0, [2, 2, 2]);
+        framebuffer.set_pixel(6, 0, [3, 3, 3]);
+        framebuffer.set_pixel(7, 0, [3, 3, 3]);
+        assert_eq!(
+            framebuffer.diff(),
+            [
+                Message::Rectangle {
+                    x: 6,
+                    y: 0,
+                    width: 2,
+                    height: 1,
+                    red: 3,
+                    green: 3,
+                    blue: 3,
+                },
+                Message::Contiguous {
+                    x: 1,
+                    y: 0,
+                    width: 3,
+                    height: 1,
+                    colors: vec![1, 1, 1, 0, 0, 0, 2, 2, 2],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rectangle_is_clipped() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.rectangle(1, 1, 10, 10, [9, 9, 9]);
+        assert_eq!(framebuffer.pixel(0, 1), Some([0, 0, 0]));
+        assert_eq!(framebuffer.pixel(1, 1), Some([9, 9, 9]));
+        assert_eq!(framebuffer.pixel(2, 1), Some([9, 9, 9]));
+        assert_eq!(framebuffer.pixel(3, 1), None);
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn draw_target() {
+        use ::embedded_graphics::Drawable as _;
+        use ::embedded_graphics::geometry::{Point, Size};
+        use ::embedded_graphics::pixelcolor::Rgb888;
+        use ::embedded_graphics::primitives::{Primitive as _, PrimitiveStyle, Rectangle};
+
+        let mut framebuffer = Framebuffer::new(4, 3);
+        Rectangle::new(Point::new(-1, 1), Size::new(3, 5))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::new(1, 2, 3)))
+            .draw(&mut framebuffer)
+            .unwrap();
+        assert_eq!(framebuffer.pixel(0, 0), Some([0, 0, 0]));
+        assert_eq!(framebuffer.pixel(0, 1), Some([1, 2, 3]));
+        assert_eq!(framebuffer.pixel(1, 2), Some([1, 2, 3]));
+        assert_eq!(framebuffer.pixel(2, 2), Some([0, 0, 0]));
+    }
+}
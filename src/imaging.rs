@@ -0,0 +1,419 @@
+//! Scale and place images on the display.
+//!
+//! The clients provide this as `display_image`.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView as _};
+
+use crate::color::Rgb;
+use crate::correction::Correction;
+
+/// 4x4 Bayer matrix with thresholds from 0 to 15
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How the image is fitted onto the display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageMode {
+    /// Scale to fit within the display keeping the aspect ratio. The uncovered border is left unchanged.
+    #[default]
+    Fit,
+    /// Scale to cover the whole display keeping the aspect ratio. What sticks out is cut off evenly.
+    Fill,
+    /// Scale to exactly the display size ignoring the aspect ratio.
+    Stretch,
+    /// Do not scale. The image is placed in the middle and what does not fit is cut off evenly.
+    Center,
+}
+
+/// Hide the coarse steps between the brightness levels of the LEDs by mixing neighbouring levels.
+///
+/// With gamma correction or a low brightness many dark colors end up on the same LED level which looks blotchy.
+/// Dithering targets the levels the LEDs actually show, see [`ImageOptions::place_corrected`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dither {
+    #[default]
+    None,
+    /// Ordered dithering with a 4x4 Bayer matrix. The pattern stays the same for similar images which avoids flickering.
+    Bayer,
+    /// Floyd-Steinberg error diffusion. Smoother, but the pattern changes with every detail of the image.
+    FloydSteinberg,
+}
+
+/// How to get an image onto the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct ImageOptions {
+    mode: ImageMode,
+    filter: FilterType,
+    dither: Dither,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageOptions {
+    /// [`ImageMode::Fit`] with the [`FilterType::Triangle`] filter and no dithering.
+    pub const fn new() -> Self {
+        Self {
+            mode: ImageMode::Fit,
+            filter: FilterType::Triangle,
+            dither: Dither::None,
+        }
+    }
+
+    pub const fn mode(mut self, mode: ImageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Resampling filter used when scaling.
+    ///
+    /// [`FilterType::Nearest`] keeps pixel art crisp, the others smooth photos.
+    pub const fn filter(mut self, filter: FilterType) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub const fn dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Scale the image and place it on a display of the given size.
+    ///
+    /// Returns `None` when nothing of the image is visible, for example when it is empty.
+    #[must_use]
+    pub fn place(
+        self,
+        image: &DynamicImage,
+        display_width: u8,
+        display_height: u8,
+    ) -> Option<Placed> {
+        self.place_for(image, display_width, display_height, None)
+    }
+
+    /// Like [`place`](Self::place) for a display which applies the given correction to the colors.
+    ///
+    /// The dithering then mixes the levels the LEDs actually show after the correction.
+    /// Without dithering this is the same as [`place`](Self::place).
+    #[must_use]
+    pub fn place_corrected(
+        self,
+        image: &DynamicImage,
+        display_width: u8,
+        display_height: u8,
+        correction: &Correction,
+    ) -> Option<Placed> {
+        self.place_for(image, display_width, display_height, Some(correction))
+    }
+
+    pub(crate) fn place_for(
+        self,
+        image: &DynamicImage,
+        display_width: u8,
+        display_height: u8,
+        correction: Option<&Correction>,
+    ) -> Option<Placed> {
+        let mut placed = self.scale(image, display_width, display_height)?;
+        if self.dither != Dither::None {
+            let quantizers = [0, 1, 2].map(|channel| Quantizer::new(correction, channel));
+            dither(self.dither, &mut placed, &quantizers);
+        }
+        Some(placed)
+    }
+
+    #[expect(clippy::cast_possible_truncation)]
+    fn scale(self, image: &DynamicImage, display_width: u8, display_height: u8) -> Option<Placed> {
+        let display_width = u32::from(display_width);
+        let display_height = u32::from(display_height);
+        if image.width() == 0 || image.height() == 0 || display_width == 0 || display_height == 0 {
+            return None;
+        }
+        let scaled = match self.mode {
+            ImageMode::Fit => image.resize(display_width, display_height, self.filter),
+            ImageMode::Fill => image.resize_to_fill(display_width, display_height, self.filter),
+            ImageMode::Stretch => image.resize_exact(display_width, display_height, self.filter),
+            ImageMode::Center => {
+                let width = image.width().min(display_width);
+                let height = image.height().min(display_height);
+                image.crop_imm(
+                    (image.width() - width) / 2,
+                    (image.height() - height) / 2,
+                    width,
+                    height,
+                )
+            }
+        };
+        let width = u8::try_from(scaled.width().min(display_width)).ok()?;
+        let height = u8::try_from(scaled.height().min(display_height)).ok()?;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let x = (u8::try_from(display_width).ok()? - width) / 2;
+        let y = (u8::try_from(display_height).ok()? - height) / 2;
+        let colors = scaled
+            .view(0, 0, width.into(), height.into())
+            .pixels()
+            .map(|(_x, _y, pixel)| {
+                let [red, green, blue, alpha] = pixel.0;
+                // Transparent parts are shown on black as the LEDs are off there
+                let [red, green, blue] = [red, green, blue]
+                    .map(|channel| ((u16::from(channel) * u16::from(alpha) + 127) / 255) as u8);
+                Rgb::new(red, green, blue)
+            })
+            .collect();
+        Some(Placed {
+            x,
+            y,
+            width,
+            height,
+            colors,
+        })
+    }
+}
+
+/// Brightness levels one channel of the LEDs can show.
+struct Quantizer {
+    /// Level for each input before rounding
+    exact: [f32; 256],
+    /// Every level the LEDs can show with the lowest input resulting in it, ascending
+    levels: Vec<(u8, u8)>,
+}
+
+impl Quantizer {
+    fn new(correction: Option<&Correction>, channel: usize) -> Self {
+        let mut exact = [0.0; 256];
+        let mut levels = Vec::new();
+        for input in 0_u8..=255 {
+            let (value, level) = correction.map_or_else(
+                || (f32::from(input), input),
+                |correction| {
+                    (
+                        correction.exact(channel, input),
+                        correction.table(channel)[usize::from(input)],
+                    )
+                },
+            );
+            exact[usize::from(input)] = value;
+            if levels.last().is_none_or(|&(last, _)| last != level) {
+                levels.push((level, input));
+            }
+        }
+        Self { exact, levels }
+    }
+
+    /// The levels right below and above the value and where the value is in between them from 0.0 to 1.0.
+    fn surrounding(&self, value: f32) -> ((u8, u8), (u8, u8), f32) {
+        let index = self
+            .levels
+            .partition_point(|&(level, _)| f32::from(level) <= value);
+        let below = self.levels[index.saturating_sub(1)];
+        let above = self.levels.get(index).copied().unwrap_or(below);
+        let fraction = if above.0 > below.0 {
+            (value - f32::from(below.0)) / f32::from(above.0 - below.0)
+        } else {
+            0.0
+        };
+        (below, above, fraction)
+    }
+}
+
+/// Replace the colors of the placed image with inputs which mix to the exact levels on average.
+fn dither(dither: Dither, placed: &mut Placed, quantizers: &[Quantizer; 3]) {
+    let width = usize::from(placed.width);
+    // Error of the current and the next row per channel for Floyd-Steinberg
+    let mut errors = [vec![[0.0_f32; 3]; width + 2], vec![[0.0_f32; 3]; width + 2]];
+    for (row, colors) in placed.colors.chunks_exact_mut(width).enumerate() {
+        for (column, color) in colors.iter_mut().enumerate() {
+            let inputs = [color.red, color.green, color.blue];
+            let mut outputs = [0; 3];
+            for (channel, quantizer) in quantizers.iter().enumerate() {
+                let exact = quantizer.exact[usize::from(inputs[channel])];
+                outputs[channel] = match dither {
+                    Dither::None => inputs[channel],
+                    Dither::Bayer => {
+                        let (below, above, fraction) = quantizer.surrounding(exact);
+                        let threshold = (f32::from(BAYER[row % 4][column % 4]) + 0.5) / 16.0;
+                        if fraction > threshold {
+                            above.1
+                        } else {
+                            below.1
+                        }
+                    }
+                    Dither::FloydSteinberg => {
+                        let value = exact + errors[0][column + 1][channel];
+                        let (below, above, fraction) = quantizer.surrounding(value);
+                        let (level, input) = if fraction >= 0.5 { above } else { below };
+                        let error = value - f32::from(level);
+                        errors[0][column + 2][channel] += error * 7.0 / 16.0;
+                        errors[1][column][channel] += error * 3.0 / 16.0;
+                        errors[1][column + 1][channel] += error * 5.0 / 16.0;
+                        errors[1][column + 2][channel] += error * 1.0 / 16.0;
+                        input
+                    }
+                };
+            }
+            let [red, green, blue] = outputs;
+            *color = Rgb::new(red, green, blue);
+        }
+        errors.swap(0, 1);
+        errors[1].fill([0.0; 3]);
+    }
+}
+
+/// Image scaled to and placed on the display, ready to be sent via `contiguous`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placed {
+    pub x: u8,
+    pub y: u8,
+    pub width: u8,
+    pub height: u8,
+    /// Row by row starting in the top left
+    pub colors: Vec<Rgb>,
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgb as Rgb8, RgbImage, Rgba, RgbaImage};
+
+    use super::*;
+
+    /// Left half red, right half blue
+    fn halves(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, _y| {
+            if x < width / 2 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        }))
+    }
+
+    fn options(mode: ImageMode) -> ImageOptions {
+        ImageOptions::new().mode(mode).filter(FilterType::Nearest)
+    }
+
+    #[test]
+    fn fit_keeps_aspect_ratio() {
+        let placed = options(ImageMode::Fit).place(&halves(8, 8), 8, 4).unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (2, 0, 4, 4)
+        );
+        assert_eq!(placed.colors[0], Rgb::RED);
+        assert_eq!(placed.colors[3], Rgb::BLUE);
+    }
+
+    #[test]
+    fn fill_cuts_off() {
+        let placed = options(ImageMode::Fill).place(&halves(4, 4), 8, 4).unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (0, 0, 8, 4)
+        );
+        let placed = options(ImageMode::Fill)
+            .place(&halves(16, 2), 4, 4)
+            .unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (0, 0, 4, 4)
+        );
+        assert_eq!(placed.colors[1], Rgb::RED);
+        assert_eq!(placed.colors[2], Rgb::BLUE);
+    }
+
+    #[test]
+    fn stretch_ignores_aspect_ratio() {
+        let placed = options(ImageMode::Stretch)
+            .place(&halves(2, 2), 8, 4)
+            .unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (0, 0, 8, 4)
+        );
+        assert_eq!(placed.colors[3], Rgb::RED);
+        assert_eq!(placed.colors[4], Rgb::BLUE);
+    }
+
+    #[test]
+    fn center_does_not_scale() {
+        let placed = options(ImageMode::Center)
+            .place(&halves(2, 2), 8, 4)
+            .unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (3, 1, 2, 2)
+        );
+        let placed = options(ImageMode::Center)
+            .place(&halves(12, 2), 8, 4)
+            .unwrap();
+        assert_eq!(
+            (placed.x, placed.y, placed.width, placed.height),
+            (0, 1, 8, 2)
+        );
+        assert_eq!(placed.colors[3], Rgb::RED);
+        assert_eq!(placed.colors[4], Rgb::BLUE);
+    }
+
+    #[test]
+    fn transparent_is_black() {
+        let image =
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([200, 100, 50, 128])));
+        let placed = ImageOptions::new().place(&image, 1, 1).unwrap();
+        assert_eq!(placed.colors, [Rgb::new(100, 50, 25)]);
+        assert_eq!(
+            ImageOptions::new().place(&DynamicImage::new_rgb8(0, 0), 8, 4),
+            None
+        );
+    }
+
+    /// Average LED level of the red channel after the correction
+    #[expect(clippy::cast_precision_loss)]
+    fn average_red(placed: &Placed, correction: &Correction) -> f32 {
+        let sum = placed
+            .colors
+            .iter()
+            .map(|color| f32::from(correction.apply(*color).red))
+            .sum::<f32>();
+        sum / placed.colors.len() as f32
+    }
+
+    #[test]
+    fn dither_without_correction_changes_nothing() {
+        let image = halves(8, 8);
+        let plain = ImageOptions::new().place(&image, 8, 8).unwrap();
+        for dither in [Dither::Bayer, Dither::FloydSteinberg] {
+            let dithered = ImageOptions::new()
+                .dither(dither)
+                .place(&image, 8, 8)
+                .unwrap();
+            assert_eq!(dithered, plain);
+        }
+    }
+
+    #[test]
+    fn dither_mixes_corrected_levels() {
+        let correction = Correction::new().gamma(2.2).brightness(0.1);
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, Rgb8([100, 0, 0])));
+        let exact = correction.exact(0, 100);
+        assert!((3.0..3.5).contains(&exact));
+
+        let plain = ImageOptions::new()
+            .place_corrected(&image, 8, 8, &correction)
+            .unwrap();
+        assert!((average_red(&plain, &correction) - 3.0).abs() < f32::EPSILON);
+
+        for dither in [Dither::Bayer, Dither::FloydSteinberg] {
+            let dithered = ImageOptions::new()
+                .dither(dither)
+                .place_corrected(&image, 8, 8, &correction)
+                .unwrap();
+            let average = average_red(&dithered, &correction);
+            assert!((average - exact).abs() < 0.05, "{dither:?} {average}");
+            assert!(dithered.colors.iter().all(|color| color.green == 0));
+        }
+    }
+}
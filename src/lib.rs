@@ -1,15 +1,33 @@
+#[cfg(feature = "image")]
+pub mod animation;
 #[cfg(feature = "tokio")]
 pub mod async_tokio;
+#[cfg(any(feature = "sync", feature = "tokio"))]
+pub mod backoff;
+pub mod canvas;
+pub mod color;
+pub mod compositor;
+pub mod correction;
+pub mod error;
+pub mod font;
+pub mod framebuffer;
+#[cfg(feature = "image")]
+pub mod imaging;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod pacing;
+#[cfg(any(feature = "sync", feature = "tokio"))]
+mod pipeline;
+pub mod planner;
+pub mod power;
+pub mod protocol;
 #[cfg(feature = "sync")]
 pub mod sync;
+#[cfg(feature = "embedded-graphics")]
+pub mod ticker;
+pub mod tiling;
+pub mod transform;
+pub mod widgets;
 
-#[cfg(any(feature = "sync", feature = "tokio"))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[non_exhaustive]
-#[repr(u8)]
-pub(crate) enum Command {
-    Fill = 1,
-    Pixel = 2,
-    Rectangle = 3,
-    Contiguous = 4,
-}
+pub use self::color::Rgb;
+pub use self::error::Error;
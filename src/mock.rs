@@ -0,0 +1,330 @@
+//! In-process stand-in for an espPixelmatrix.
+//!
+//! The [`MockServer`] speaks protocol version 1 on a local TCP port and keeps an in-memory framebuffer
+//! of everything the connected clients sent so tests can assert on what would have been displayed.
+
+use core::time::Duration;
+use std::collections::HashMap;
+use std::io::{BufReader, Write as _};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::error::Error;
+use crate::protocol::{Handshake, Message};
+
+pub struct MockServer {
+    address: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Clones of the open connections by their number so they can be closed from the outside.
+    connections: Mutex<HashMap<usize, TcpStream>>,
+    changed: Condvar,
+    shutdown: AtomicBool,
+}
+
+struct State {
+    width: u8,
+    height: u8,
+    pixels: Vec<[u8; 3]>,
+    messages: Vec<Message>,
+    received: usize,
+    accepted: usize,
+    stalled: bool,
+}
+
+impl MockServer {
+    /// Start a server on a random free port of the loopback interface.
+    ///
+    /// # Errors
+    /// Errors when the port could not be bound.
+    pub fn start(width: u8, height: u8) -> std::io::Result<Self> {
+        Self::bind("127.0.0.1:0", width, height)
+    }
+
+    /// Start a server listening on the given address.
+    ///
+    /// # Errors
+    /// Errors when the address could not be bound.
+    pub fn bind<Address: ToSocketAddrs>(
+        address: Address,
+        width: u8,
+        height: u8,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                width,
+                height,
+                pixels: vec![[0; 3]; usize::from(width) * usize::from(height)],
+                messages: Vec::new(),
+                received: 0,
+                accepted: 0,
+                stalled: false,
+            }),
+            connections: Mutex::new(HashMap::new()),
+            changed: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let accepting = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let id = {
+                    let mut state = accepting.lock();
+                    state.accepted += 1;
+                    state.accepted
+                };
+                if let Ok(clone) = stream.try_clone() {
+                    accepting.connections().insert(id, clone);
+                }
+                let connection = Arc::clone(&accepting);
+                std::thread::spawn(move || {
+                    // The connection is closed on any error, just like the firmware would do.
+                    _ = handle_connection(&connection, stream);
+                    connection.connections().remove(&id);
+                });
+            }
+        });
+
+        Ok(Self { address, shared })
+    }
+
+    /// The address clients should connect to.
+    #[must_use]
+    pub const fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u8 {
+        self.shared.lock().width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u8 {
+        self.shared.lock().height
+    }
+
+    /// Current color of the given pixel or `None` when it is outside of the matrix.
+    #[must_use]
+    pub fn pixel(&self, x: u8, y: u8) -> Option<[u8; 3]> {
+        let state = self.shared.lock();
+        state
+            .index(u16::from(x), u16::from(y))
+            .map(|index| state.pixels[index])
+    }
+
+    /// All pixels of the matrix, row by row starting in the top left.
+    #[must_use]
+    pub fn framebuffer(&self) -> Vec<[u8; 3]> {
+        self.shared.lock().pixels.clone()
+    }
+
+    /// Every [`Message`] received so far in the order it was received.
+    ///
+    /// Messages removed by [`take_messages`](Self::take_messages) are not included.
+    #[must_use]
+    pub fn messages(&self) -> Vec<Message> {
+        self.shared.lock().messages.clone()
+    }
+
+    /// Remove and return every [`Message`] received since the last call.
+    ///
+    /// Useful for long running servers which would otherwise keep every message forever.
+    #[must_use]
+    pub fn take_messages(&self) -> Vec<Message> {
+        core::mem::take(&mut self.shared.lock().messages)
+    }
+
+    /// Amount of messages received since the server started.
+    #[must_use]
+    pub fn received(&self) -> usize {
+        self.shared.lock().received
+    }
+
+    /// Amount of connections accepted since the server started.
+    #[must_use]
+    pub fn accepted(&self) -> usize {
+        self.shared.lock().accepted
+    }
+
+    /// Close every currently open connection like a rebooting matrix would.
+    ///
+    /// New connections are still accepted.
+    pub fn disconnect_clients(&self) {
+        let connections = core::mem::take(&mut *self.shared.connections());
+        for connection in connections.into_values() {
+            _ = connection.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Stop reading from the connections like a busy matrix would.
+    ///
+    /// The clients keep sending until the buffers of the connection are full and their writes time out.
+    pub fn stall(&self, stalled: bool) {
+        self.shared.lock().stalled = stalled;
+        self.shared.changed.notify_all();
+    }
+
+    /// Block until at least `count` messages were received in total.
+    ///
+    /// Returns `false` when the timeout elapsed first.
+    #[must_use]
+    pub fn wait_for_messages(&self, count: usize, timeout: Duration) -> bool {
+        let (_state, result) = self
+            .shared
+            .changed
+            .wait_timeout_while(self.shared.lock(), timeout, |state| state.received < count)
+            .unwrap_or_else(PoisonError::into_inner);
+        !result.timed_out()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        self.stall(false);
+        // Wake up the accept loop so it notices the shutdown
+        _ = TcpStream::connect(self.address);
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn connections(&self) -> MutexGuard<'_, HashMap<usize, TcpStream>> {
+        self.connections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        (x < u16::from(self.width) && y < u16::from(self.height))
+            .then(|| usize::from(y) * usize::from(self.width) + usize::from(x))
+    }
+
+    fn set(&mut self, x: u16, y: u16, color: [u8; 3]) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    fn apply(&mut self, message: &Message) {
+        match *message {
+            Message::Fill { red, green, blue } => self.pixels.fill([red, green, blue]),
+            Message::Pixel {
+                x,
+                y,
+                red,
+                green,
+                blue,
+            } => self.set(u16::from(x), u16::from(y), [red, green, blue]),
+            Message::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => {
+                for y in area(y, height) {
+                    for x in area(x, width) {
+                        self.set(x, y, [red, green, blue]);
+                    }
+                }
+            }
+            Message::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => {
+                let positions = area(y, height).flat_map(|y| area(x, width).map(move |x| (x, y)));
+                for ((x, y), color) in positions.zip(colors.chunks_exact(3)) {
+                    self.set(x, y, [color[0], color[1], color[2]]);
+                }
+            }
+        }
+    }
+}
+
+/// Coordinates covered by an area. Can exceed the matrix which is ignored by [`State::set`].
+fn area(start: u8, length: u8) -> core::ops::Range<u16> {
+    u16::from(start)..u16::from(start) + u16::from(length)
+}
+
+fn handle_connection(shared: &Shared, mut stream: TcpStream) -> Result<(), Error> {
+    let handshake = {
+        let state = shared.lock();
+        Handshake {
+            width: state.width,
+            height: state.height,
+        }
+    };
+    stream.write_all(&handshake.encode())?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    loop {
+        drop(
+            shared
+                .changed
+                .wait_while(shared.lock(), |state| state.stalled)
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        let Some(message) = Message::read_from(&mut reader)? else {
+            break;
+        };
+        if shared.shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        let mut state = shared.lock();
+        state.apply(&message);
+        state.messages.push(message);
+        state.received += 1;
+        drop(state);
+        shared.changed.notify_all();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read as _;
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn closed_connections_are_forgotten() {
+        let server = MockServer::start(2, 2).unwrap();
+        for _ in 0..3 {
+            let mut stream = TcpStream::connect(server.address()).unwrap();
+            let mut handshake = [0; 3];
+            stream.read_exact(&mut handshake).unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !server.shared.connections().is_empty() {
+            assert!(Instant::now() < deadline, "connections were kept open");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(server.accepted(), 3);
+    }
+}
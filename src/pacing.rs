@@ -0,0 +1,272 @@
+//! Timing of a render loop running at a target frame rate.
+//!
+//! The clients provide this as `FrameLoop`.
+
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Amount of recent frames the [`FrameStats`] are calculated from.
+const WINDOW: usize = 120;
+
+/// Information about the frame which is about to be rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    /// Counts every frame including the skipped ones. Starts with 0.
+    pub number: u64,
+    /// Frames skipped right before this one because the previous one took too long.
+    pub skipped: u64,
+    /// Time since the first frame
+    pub elapsed: Duration,
+    /// Time since the previous frame
+    pub delta: Duration,
+}
+
+/// Decides when to render the next frame.
+///
+/// Frames are scheduled at fixed intervals from the first one, so the time spent rendering and sending is accounted for.
+/// When a frame took longer than one interval, the missed frames are skipped instead of rendered in a hurry.
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    interval: Duration,
+    start: Option<Instant>,
+    previous: Option<Instant>,
+    deadline: Option<Instant>,
+    number: u64,
+    skipped: u64,
+    stats: FrameStats,
+}
+
+impl Pacer {
+    /// Pace with the given frames per second. [`f32::INFINITY`] renders as fast as possible.
+    #[must_use]
+    pub fn new(fps: f32) -> Self {
+        Self::with_interval(Duration::from_secs_f32(1.0 / fps.max(0.001)))
+    }
+
+    #[must_use]
+    pub const fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            start: None,
+            previous: None,
+            deadline: None,
+            number: 0,
+            skipped: 0,
+            stats: FrameStats::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    #[must_use]
+    pub const fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Start rendering a frame now.
+    pub fn begin(&mut self, now: Instant) -> Frame {
+        let start = *self.start.get_or_insert(now);
+        let previous = self.previous.replace(now).unwrap_or(now);
+        self.deadline.get_or_insert(now);
+        let frame = Frame {
+            number: self.number,
+            skipped: self.skipped,
+            elapsed: now.saturating_duration_since(start),
+            delta: now.saturating_duration_since(previous),
+        };
+        self.number += 1;
+        self.skipped = 0;
+        frame
+    }
+
+    /// The frame is sent and flushed. Returns how long to wait before the next frame begins.
+    pub fn finish(&mut self, now: Instant, bytes: u64, flush_latency: Duration) -> Duration {
+        self.stats.record(now, bytes, flush_latency);
+        if self.interval.is_zero() {
+            return Duration::ZERO;
+        }
+        let deadline = self.deadline.unwrap_or(now) + self.interval;
+        let behind = now.saturating_duration_since(deadline).as_nanos();
+        let skipped = behind / self.interval.as_nanos().max(1);
+        let skipped = u64::try_from(skipped).unwrap_or(u64::MAX);
+        let deadline = deadline
+            + self
+                .interval
+                .saturating_mul(u32::try_from(skipped).unwrap_or(u32::MAX));
+        self.deadline = Some(deadline);
+        self.number = self.number.saturating_add(skipped);
+        self.skipped = skipped;
+        self.stats.skipped_frames = self.stats.skipped_frames.saturating_add(skipped);
+        deadline.saturating_duration_since(now)
+    }
+}
+
+/// Statistics about the recently rendered frames.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    frames: u64,
+    skipped_frames: u64,
+    /// Finish time, bytes sent and flush latency of the recent frames
+    recent: VecDeque<(Instant, u64, Duration)>,
+}
+
+impl FrameStats {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            frames: 0,
+            skipped_frames: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, finished: Instant, bytes: u64, flush_latency: Duration) {
+        self.frames += 1;
+        if self.recent.len() == WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((finished, bytes, flush_latency));
+    }
+
+    /// Amount of rendered frames.
+    #[must_use]
+    pub const fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Amount of frames skipped because rendering and sending took too long.
+    #[must_use]
+    pub const fn skipped_frames(&self) -> u64 {
+        self.skipped_frames
+    }
+
+    /// Actually achieved frames per second over the recent frames.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn fps(&self) -> Option<f32> {
+        let (first, ..) = self.recent.front()?;
+        let (last, ..) = self.recent.back()?;
+        let seconds = last.saturating_duration_since(*first).as_secs_f32();
+        (seconds > 0.0).then(|| (self.recent.len() - 1) as f32 / seconds)
+    }
+
+    /// Average amount of bytes sent per frame over the recent frames.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn bytes_per_frame(&self) -> Option<f32> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let sum = self.recent.iter().map(|(_, bytes, _)| bytes).sum::<u64>();
+        Some(sum as f32 / self.recent.len() as f32)
+    }
+
+    /// Flush latency of the recent frames which the given percentage of frames did not exceed.
+    ///
+    /// `percentile` is between 0.0 and 100.0, for example 50.0 for the median or 99.0.
+    #[must_use]
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn flush_latency(&self, percentile: f32) -> Option<Duration> {
+        let mut latencies = self
+            .recent
+            .iter()
+            .map(|(.., latency)| *latency)
+            .collect::<Vec<_>>();
+        latencies.sort_unstable();
+        let last = latencies.len().checked_sub(1)?;
+        let index = (percentile.clamp(0.0, 100.0) / 100.0 * last as f32).round() as usize;
+        latencies.get(index).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn accounts_for_render_time() {
+        let start = Instant::now();
+        let mut pacer = Pacer::with_interval(INTERVAL);
+        let frame = pacer.begin(start);
+        assert_eq!(frame.number, 0);
+        assert_eq!(frame.elapsed, Duration::ZERO);
+        let wait = pacer.finish(
+            start + Duration::from_millis(30),
+            10,
+            Duration::from_millis(5),
+        );
+        assert_eq!(wait, Duration::from_millis(70));
+
+        let frame = pacer.begin(start + INTERVAL);
+        assert_eq!(frame.number, 1);
+        assert_eq!(frame.skipped, 0);
+        assert_eq!(frame.delta, INTERVAL);
+    }
+
+    #[test]
+    fn skips_frames_when_behind() {
+        let start = Instant::now();
+        let mut pacer = Pacer::with_interval(INTERVAL);
+        pacer.begin(start);
+        // Slightly late is rendered right away
+        let wait = pacer.finish(start + Duration::from_millis(150), 0, Duration::ZERO);
+        assert_eq!(wait, Duration::ZERO);
+        let frame = pacer.begin(start + Duration::from_millis(150));
+        assert_eq!(frame.skipped, 0);
+
+        // Way too late skips the missed frames and keeps the rhythm
+        let wait = pacer.finish(start + Duration::from_millis(520), 0, Duration::ZERO);
+        assert_eq!(wait, Duration::ZERO);
+        let frame = pacer.begin(start + Duration::from_millis(520));
+        assert_eq!(frame.number, 5);
+        assert_eq!(frame.skipped, 3);
+        assert_eq!(pacer.stats().skipped_frames(), 3);
+        let wait = pacer.finish(start + Duration::from_millis(540), 0, Duration::ZERO);
+        assert_eq!(wait, Duration::from_millis(60));
+    }
+
+    #[test]
+    fn unlimited() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new(f32::INFINITY);
+        assert_eq!(pacer.interval(), Duration::ZERO);
+        pacer.begin(start);
+        let wait = pacer.finish(start + Duration::from_secs(1), 0, Duration::ZERO);
+        assert_eq!(wait, Duration::ZERO);
+        let frame = pacer.begin(start + Duration::from_secs(1));
+        assert_eq!(frame.number, 1);
+        assert_eq!(frame.skipped, 0);
+    }
+
+    #[test]
+    fn stats() {
+        let start = Instant::now();
+        let mut stats = FrameStats::new();
+        assert_eq!(stats.fps(), None);
+        assert_eq!(stats.bytes_per_frame(), None);
+        assert_eq!(stats.flush_latency(50.0), None);
+        for index in 0..5 {
+            stats.record(
+                start + INTERVAL * index,
+                u64::from(index) * 10,
+                Duration::from_millis(u64::from(index)),
+            );
+        }
+        assert_eq!(stats.frames(), 5);
+        assert!((stats.fps().unwrap() - 10.0).abs() < 0.01);
+        assert!((stats.bytes_per_frame().unwrap() - 20.0).abs() < 0.01);
+        assert_eq!(stats.flush_latency(0.0), Some(Duration::ZERO));
+        assert_eq!(stats.flush_latency(50.0), Some(Duration::from_millis(2)));
+        assert_eq!(stats.flush_latency(100.0), Some(Duration::from_millis(4)));
+    }
+}
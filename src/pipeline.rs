@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::correction::Correction;
+use crate::error::Error;
+use crate::power::{PowerBudget, PowerDelta, PowerLimiter};
+use crate::protocol::{Bounds, Message};
+
+/// Everything the clients do to a [`Message`] before it is written to the connection.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    width: u8,
+    height: u8,
+    bounds: Bounds,
+    correction: Option<Correction>,
+    power: Option<Arc<Mutex<PowerLimiter>>>,
+}
+
+impl Pipeline {
+    pub fn new(
+        width: u8,
+        height: u8,
+        bounds: Bounds,
+        correction: Option<Correction>,
+        power_budget: Option<PowerBudget>,
+    ) -> Self {
+        let power = power_budget
+            .map(|budget| Arc::new(Mutex::new(PowerLimiter::new(budget, width, height))));
+        Self {
+            width,
+            height,
+            bounds,
+            correction,
+            power,
+        }
+    }
+
+    pub const fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// Encode the messages to be sent together.
+    ///
+    /// Nothing is changed until the result is [committed](Self::commit) after it was written.
+    pub fn prepare(&self, messages: &[Message]) -> Result<Prepared, Error> {
+        let mut prepared = Vec::with_capacity(messages.len());
+        for message in messages {
+            let Some(message) = self.bounds.apply(message, self.width, self.height)? else {
+                continue;
+            };
+            prepared.push(match &self.correction {
+                Some(correction) => Cow::Owned(correction.apply_message(&message)),
+                None => message,
+            });
+        }
+        let power = match &self.power {
+            Some(power) => Some(
+                power
+                    .lock()
+                    .map_err(|_poisoned| Error::Poisoned)?
+                    .limit(&mut prepared),
+            ),
+            None => None,
+        };
+        let mut bytes = Vec::new();
+        for message in &prepared {
+            message.encode(&mut bytes);
+        }
+        Ok(Prepared { bytes, power })
+    }
+
+    /// Remember the prepared messages as shown once they were written.
+    pub fn commit(&self, prepared: Prepared) {
+        if let (Some(power), Some(delta)) = (&self.power, prepared.power) {
+            power
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .commit(delta);
+        }
+    }
+
+    #[cfg(feature = "image")]
+    pub const fn correction(&self) -> Option<&Correction> {
+        self.correction.as_ref()
+    }
+
+    pub fn estimated_milliamps(&self) -> Option<f32> {
+        let power = self.power.as_ref()?;
+        let power = power.lock().unwrap_or_else(PoisonError::into_inner);
+        Some(power.estimated_milliamps())
+    }
+}
+
+/// Messages encoded by [`Pipeline::prepare`].
+pub struct Prepared {
+    pub bytes: Vec<u8>,
+    /// Pixels the messages change on the power limiter
+    power: Option<PowerDelta>,
+}
@@ -0,0 +1,422 @@
+//! Choose cheap command sequences to update the matrix.
+//!
+//! Each [`Message`] has a different cost on the wire (see [`Message::encoded_len`]).
+//! The planner compares a few strategies and returns the one with the fewest bytes:
+//!
+//! - Patching only the changed pixels with solid [`Message::Rectangle`]s, [`Message::Pixel`]s and row wise [`Message::Contiguous`] spans
+//! - One [`Message::Contiguous`] covering the bounding box of all changes
+//! - [`Message::Fill`] with the most common color followed by patching every pixel of another color
+//!
+//! The strategies are greedy so the result is not guaranteed to be the optimum but it is cheap to compute.
+//!
+//! The result can be sent with the `send` method of both the sync and the tokio client.
+
+use std::collections::HashMap;
+
+use crate::protocol::Message;
+
+/// Plan the messages needed to show `target` when the pixels not marked in `changed` already show their target color.
+///
+/// Both `target` and `changed` are row by row starting in the top left.
+///
+/// # Panics
+/// Panics when `target` or `changed` do not contain `width * height` entries.
+#[must_use]
+pub fn plan(width: u8, height: u8, target: &[[u8; 3]], changed: &[bool]) -> Vec<Message> {
+    let total = usize::from(width) * usize::from(height);
+    assert_eq!(target.len(), total, "target has to contain every pixel");
+    assert_eq!(changed.len(), total, "changed has to contain every pixel");
+    if !changed.contains(&true) {
+        return Vec::new();
+    }
+
+    let grid = Grid {
+        width: usize::from(width),
+        height: usize::from(height),
+        target,
+    };
+
+    let mut best = grid.patch(changed.to_vec());
+    let mut best_cost = cost(&best);
+
+    let candidate = grid.bounding_box(changed);
+    let candidate_cost = cost(&candidate);
+    if candidate_cost < best_cost {
+        best = candidate;
+        best_cost = candidate_cost;
+    }
+
+    if let Some(dominant) = most_common(target) {
+        let pending = target.iter().map(|color| color != &dominant).collect();
+        let [red, green, blue] = dominant;
+        let mut candidate = vec![Message::Fill { red, green, blue }];
+        candidate.extend(grid.patch(pending));
+        if cost(&candidate) < best_cost {
+            best = candidate;
+        }
+    }
+
+    best
+}
+
+/// Bytes needed on the wire for all the messages.
+#[must_use]
+pub fn cost(messages: &[Message]) -> usize {
+    messages.iter().map(Message::encoded_len).sum()
+}
+
+fn most_common(colors: &[[u8; 3]]) -> Option<[u8; 3]> {
+    let mut counts = HashMap::<[u8; 3], usize>::new();
+    for color in colors {
+        *counts.entry(*color).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        // Include the color to stay deterministic on ties
+        .max_by_key(|(color, count)| (*count, *color))
+        .map(|(color, _count)| color)
+}
+
+/// Cost of updating `count` pixels of a single row without rectangles.
+const fn row_cost(count: usize) -> usize {
+    match count {
+        0 => 0,
+        1 => 6,
+        _ => 5 + 3 * count,
+    }
+}
+
+struct Grid<'target> {
+    width: usize,
+    height: usize,
+    target: &'target [[u8; 3]],
+}
+
+impl Grid<'_> {
+    const fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Update every pending pixel.
+    ///
+    /// Pixels which are not pending already show their target color.
+    /// They may be overwritten with it when this is cheaper.
+    fn patch(&self, mut pending: Vec<bool>) -> Vec<Message> {
+        let mut messages = self.rectangles(&mut pending);
+        for y in 0..self.height {
+            let row = self.index(0, y)..self.index(0, y + 1);
+            let row_pending = &pending[row.clone()];
+            let row_target = &self.target[row];
+            let mut x = 0;
+            while x < self.width {
+                if !row_pending[x] {
+                    x += 1;
+                    continue;
+                }
+                // Include single pixels in between as starting a new message would cost more than resending it
+                let mut end = x + 1;
+                while end < self.width
+                    && (row_pending[end] || row_pending.get(end + 1).copied().unwrap_or(false))
+                {
+                    end += 1;
+                }
+                messages.push(row_message(x, y, &row_target[x..end]));
+                x = end;
+            }
+        }
+        messages
+    }
+
+    /// Find solid colored areas which are cheaper to send as [`Message::Rectangle`].
+    ///
+    /// Covered pixels are no longer pending.
+    fn rectangles(&self, pending: &mut [bool]) -> Vec<Message> {
+        let mut messages = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !pending[self.index(x, y)] {
+                    continue;
+                }
+                let color = self.target[self.index(x, y)];
+                let same = |x: usize, y: usize| self.target[self.index(x, y)] == color;
+
+                let mut width = 1;
+                while x + width < self.width && same(x + width, y) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while y + height < self.height && (x..x + width).all(|x| same(x, y + height)) {
+                    height += 1;
+                }
+
+                let alternative = (y..y + height)
+                    .map(|y| {
+                        let count = (x..x + width)
+                            .filter(|x| pending[self.index(*x, y)])
+                            .count();
+                        row_cost(count)
+                    })
+                    .sum::<usize>();
+                if alternative <= 8 {
+                    continue;
+                }
+
+                for y in y..y + height {
+                    for x in x..x + width {
+                        pending[self.index(x, y)] = false;
+                    }
+                }
+                let [red, green, blue] = color;
+                #[expect(clippy::cast_possible_truncation)]
+                messages.push(Message::Rectangle {
+                    x: x as u8,
+                    y: y as u8,
+                    width: width as u8,
+                    height: height as u8,
+                    red,
+                    green,
+                    blue,
+                });
+            }
+        }
+        messages
+    }
+
+    /// One [`Message::Contiguous`] over the area containing every pending pixel.
+    #[expect(clippy::cast_possible_truncation)]
+    fn bounding_box(&self, pending: &[bool]) -> Vec<Message> {
+        let positions = || {
+            pending
+                .iter()
+                .enumerate()
+                .filter(|(_index, pending)| **pending)
+                .map(|(index, _pending)| (index % self.width, index / self.width))
+        };
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+            positions().map(|(x, _y)| x).min(),
+            positions().map(|(x, _y)| x).max(),
+            positions().map(|(_x, y)| y).min(),
+            positions().map(|(_x, y)| y).max(),
+        ) else {
+            return Vec::new();
+        };
+        let colors = (min_y..=max_y)
+            .flat_map(|y| &self.target[self.index(min_x, y)..=self.index(max_x, y)])
+            .flatten()
+            .copied()
+            .collect();
+        vec![Message::Contiguous {
+            x: min_x as u8,
+            y: min_y as u8,
+            width: (max_x - min_x + 1) as u8,
+            height: (max_y - min_y + 1) as u8,
+            colors,
+        }]
+    }
+}
+
+#[expect(clippy::cast_possible_truncation)]
+fn row_message(x: usize, y: usize, colors: &[[u8; 3]]) -> Message {
+    let x = x as u8;
+    let y = y as u8;
+    let [red, green, blue] = colors[0];
+    if colors.len() == 1 {
+        Message::Pixel {
+            x,
+            y,
+            red,
+            green,
+            blue,
+        }
+    } else if colors.iter().all(|color| color == &colors[0]) {
+        Message::Rectangle {
+            x,
+            y,
+            width: colors.len() as u8,
+            height: 1,
+            red,
+            green,
+            blue,
+        }
+    } else {
+        Message::Contiguous {
+            x,
+            y,
+            width: colors.len() as u8,
+            height: 1,
+            colors: colors.as_flattened().to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u8 = 8;
+    const HEIGHT: u8 = 4;
+
+    fn apply(pixels: &mut [[u8; 3]], messages: &[Message]) {
+        let width = usize::from(WIDTH);
+        for message in messages {
+            match message {
+                Message::Fill { red, green, blue } => pixels.fill([*red, *green, *blue]),
+                Message::Pixel {
+                    x,
+                    y,
+                    red,
+                    green,
+                    blue,
+                } => pixels[usize::from(*y) * width + usize::from(*x)] = [*red, *green, *blue],
+                Message::Rectangle {
+                    x,
+                    y,
+                    width: area_width,
+                    height: area_height,
+                    red,
+                    green,
+                    blue,
+                } => {
+                    for y in *y..y + area_height {
+                        for x in *x..x + area_width {
+                            pixels[usize::from(y) * width + usize::from(x)] = [*red, *green, *blue];
+                        }
+                    }
+                }
+                Message::Contiguous {
+                    x,
+                    y,
+                    width: area_width,
+                    height: area_height,
+                    colors,
+                } => {
+                    let mut colors = colors.chunks_exact(3);
+                    for y in *y..y + area_height {
+                        for x in *x..x + area_width {
+                            let color = colors.next().unwrap();
+                            pixels[usize::from(y) * width + usize::from(x)] =
+                                [color[0], color[1], color[2]];
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Plan the transition and check it results in the target.
+    fn check(before: &[[u8; 3]], target: &[[u8; 3]]) -> Vec<Message> {
+        let changed = before
+            .iter()
+            .zip(target)
+            .map(|(before, target)| before != target)
+            .collect::<Vec<_>>();
+        let messages = plan(WIDTH, HEIGHT, target, &changed);
+        let mut result = before.to_vec();
+        apply(&mut result, &messages);
+        assert_eq!(result, target);
+        messages
+    }
+
+    fn black() -> Vec<[u8; 3]> {
+        vec![[0; 3]; usize::from(WIDTH) * usize::from(HEIGHT)]
+    }
+
+    fn noise() -> Vec<[u8; 3]> {
+        (0..usize::from(WIDTH) * usize::from(HEIGHT))
+            .map(|index| {
+                #[expect(clippy::cast_possible_truncation)]
+                let index = index as u8;
+                [index, index.wrapping_mul(7), index.wrapping_mul(13)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nothing_changed() {
+        assert_eq!(check(&black(), &black()), []);
+    }
+
+    #[test]
+    fn single_pixel() {
+        let mut target = black();
+        target[9] = [1, 2, 3];
+        assert_eq!(
+            check(&black(), &target),
+            [Message::Pixel {
+                x: 1,
+                y: 1,
+                red: 1,
+                green: 2,
+                blue: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn solid_block_is_rectangle() {
+        let mut target = black();
+        for index in [9, 10, 11, 17, 18, 19] {
+            target[index] = [5, 5, 5];
+        }
+        assert_eq!(
+            check(&black(), &target),
+            [Message::Rectangle {
+                x: 1,
+                y: 1,
+                width: 3,
+                height: 2,
+                red: 5,
+                green: 5,
+                blue: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn one_color_is_fill() {
+        let target = vec![[9, 9, 9]; black().len()];
+        assert_eq!(
+            check(&noise(), &target),
+            [Message::Fill {
+                red: 9,
+                green: 9,
+                blue: 9,
+            }]
+        );
+    }
+
+    #[test]
+    fn mostly_one_color_is_fill_with_patches() {
+        let mut target = vec![[9, 9, 9]; black().len()];
+        target[0] = [1, 1, 1];
+        target[31] = [2, 2, 2];
+        let messages = check(&noise(), &target);
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0], Message::Fill { .. }));
+    }
+
+    #[test]
+    fn noise_is_single_contiguous() {
+        let messages = check(&black(), &noise());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(cost(&messages), 5 + 3 * black().len());
+    }
+
+    #[test]
+    fn never_worse_than_pixels() {
+        let before = noise();
+        let mut target = noise();
+        for (index, color) in target.iter_mut().enumerate() {
+            if index % 3 == 0 {
+                *color = [42, 0, 0];
+            }
+        }
+        let changed = before
+            .iter()
+            .zip(&target)
+            .filter(|(before, target)| before != target)
+            .count();
+        let messages = check(&before, &target);
+        assert!(cost(&messages) <= changed * 6);
+    }
+}
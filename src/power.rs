@@ -0,0 +1,347 @@
+//! Estimate the current drawn by the LEDs and dim colors which would exceed a budget.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::color::Rgb;
+use crate::protocol::Message;
+
+/// Current limit and characteristics of the LEDs.
+///
+/// The defaults match WS2812 LEDs: 20 mA per channel at full intensity and 1 mA per LED while idle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[must_use]
+pub struct PowerBudget {
+    pub max_milliamps: f32,
+    pub milliamps_per_channel: f32,
+    pub idle_milliamps_per_pixel: f32,
+}
+
+impl PowerBudget {
+    pub const fn new(max_milliamps: f32) -> Self {
+        Self {
+            max_milliamps,
+            milliamps_per_channel: 20.0,
+            idle_milliamps_per_pixel: 1.0,
+        }
+    }
+
+    /// Current drawn by one channel at full intensity.
+    pub const fn milliamps_per_channel(mut self, milliamps: f32) -> Self {
+        self.milliamps_per_channel = milliamps;
+        self
+    }
+
+    /// Current drawn by one LED while it is dark.
+    pub const fn idle_milliamps_per_pixel(mut self, milliamps: f32) -> Self {
+        self.idle_milliamps_per_pixel = milliamps;
+        self
+    }
+}
+
+/// Tracks what the display shows in order to dim messages which would exceed the [`PowerBudget`].
+#[derive(Debug, Clone)]
+pub struct PowerLimiter {
+    budget: PowerBudget,
+    width: u8,
+    height: u8,
+    pixels: Vec<[u8; 3]>,
+    channel_sum: u64,
+}
+
+impl PowerLimiter {
+    #[must_use]
+    pub fn new(budget: PowerBudget, width: u8, height: u8) -> Self {
+        Self {
+            budget,
+            width,
+            height,
+            pixels: vec![[0; 3]; usize::from(width) * usize::from(height)],
+            channel_sum: 0,
+        }
+    }
+
+    /// Estimated current drawn by the display with everything sent so far.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn estimated_milliamps(&self) -> f32 {
+        let channels = self.channel_sum as f32 / 255.0;
+        let idle = self.pixels.len() as f32 * self.budget.idle_milliamps_per_pixel;
+        channels.mul_add(self.budget.milliamps_per_channel, idle)
+    }
+
+    /// Dim the messages of a frame when together they would exceed the budget.
+    ///
+    /// Every message is dimmed by the same factor, so the frame keeps its look.
+    /// The messages have to fit on the display, see [`Message::validate`].
+    /// The limiter only remembers them as shown once the returned [`PowerDelta`] is [committed](Self::commit).
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn limit(&self, messages: &mut [Cow<'_, Message>]) -> PowerDelta {
+        let mut pixels = HashMap::new();
+        for message in messages.iter() {
+            self.for_each_pixel(message, |index, color| {
+                pixels.insert(index, color.to_array());
+            });
+        }
+
+        let replaced: u64 = pixels
+            .keys()
+            .map(|index| channel_sum(self.pixels[*index]))
+            .sum();
+        let others = self.channel_sum - replaced;
+        let added: u64 = pixels.values().copied().map(channel_sum).sum();
+
+        let idle = self.pixels.len() as f32 * self.budget.idle_milliamps_per_pixel;
+        let allowed = ((self.budget.max_milliamps - idle).max(0.0)
+            / self.budget.milliamps_per_channel
+            * 255.0) as u64;
+        if others + added <= allowed {
+            return PowerDelta {
+                pixels,
+                channel_sum: others + added,
+            };
+        }
+
+        let factor = allowed.saturating_sub(others) as f32 / added as f32;
+        let dim = |color: [u8; 3]| color.map(|channel| (f32::from(channel) * factor) as u8);
+        for message in messages.iter_mut() {
+            message.to_mut().map_colors(|color| {
+                let [red, green, blue] = dim(color.to_array());
+                Rgb::new(red, green, blue)
+            });
+        }
+        // The last message on a pixel decides its color, dimming it is the same as dimming the message
+        for pixel in pixels.values_mut() {
+            *pixel = dim(*pixel);
+        }
+        let added: u64 = pixels.values().copied().map(channel_sum).sum();
+        PowerDelta {
+            pixels,
+            channel_sum: others + added,
+        }
+    }
+
+    /// Remember the pixels of a [`limit`](Self::limit)ed frame as shown.
+    pub fn commit(&mut self, delta: PowerDelta) {
+        for (index, color) in delta.pixels {
+            self.pixels[index] = color;
+        }
+        self.channel_sum = delta.channel_sum;
+    }
+
+    /// Call `apply` with the index and color of every pixel the message covers.
+    fn for_each_pixel(&self, message: &Message, mut apply: impl FnMut(usize, Rgb)) {
+        let (x, y, width, height, colors) = match *message {
+            Message::Fill { red, green, blue } => (
+                0,
+                0,
+                self.width,
+                self.height,
+                Colors::Single(Rgb::new(red, green, blue)),
+            ),
+            Message::Pixel {
+                x,
+                y,
+                red,
+                green,
+                blue,
+            } => (x, y, 1, 1, Colors::Single(Rgb::new(red, green, blue))),
+            Message::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => (
+                x,
+                y,
+                width,
+                height,
+                Colors::Single(Rgb::new(red, green, blue)),
+            ),
+            Message::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => (x, y, width, height, Colors::Contiguous(colors)),
+        };
+        let display_width = usize::from(self.width);
+        let mut index = 0;
+        for y in y..y.saturating_add(height).min(self.height) {
+            for x in x..x.saturating_add(width).min(self.width) {
+                let color = match colors {
+                    Colors::Single(color) => color,
+                    Colors::Contiguous(colors) => {
+                        let offset = index * 3;
+                        Rgb::new(colors[offset], colors[offset + 1], colors[offset + 2])
+                    }
+                };
+                index += 1;
+                apply(usize::from(y) * display_width + usize::from(x), color);
+            }
+        }
+    }
+}
+
+/// Pixels changed by a frame, see [`PowerLimiter::limit`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct PowerDelta {
+    pixels: HashMap<usize, [u8; 3]>,
+    channel_sum: u64,
+}
+
+enum Colors<'colors> {
+    Single(Rgb),
+    Contiguous(&'colors [u8]),
+}
+
+fn channel_sum([red, green, blue]: [u8; 3]) -> u64 {
+    u64::from(red) + u64::from(green) + u64::from(blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: PowerBudget = PowerBudget::new(100.0)
+        .milliamps_per_channel(10.0)
+        .idle_milliamps_per_pixel(0.0);
+
+    fn fill(color: Rgb) -> Message {
+        Message::Fill {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        }
+    }
+
+    /// Limit a frame of a single message
+    fn limit(limiter: &mut PowerLimiter, message: Message) -> Message {
+        let mut messages = [Cow::Owned(message)];
+        let delta = limiter.limit(&mut messages);
+        limiter.commit(delta);
+        let [message] = messages;
+        message.into_owned()
+    }
+
+    #[test]
+    fn within_budget_is_unchanged() {
+        let mut limiter = PowerLimiter::new(BUDGET, 2, 2);
+        let message = fill(Rgb::new(255, 255, 0));
+        let mut messages = [Cow::Borrowed(&message)];
+        let delta = limiter.limit(&mut messages);
+        assert!(limiter.estimated_milliamps().abs() < 0.01);
+        limiter.commit(delta);
+        assert!(matches!(messages, [Cow::Borrowed(_)]));
+        assert!((limiter.estimated_milliamps() - 80.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn fill_is_dimmed() {
+        let mut limiter = PowerLimiter::new(BUDGET, 2, 2);
+        let dimmed = limit(&mut limiter, fill(Rgb::WHITE));
+        assert_eq!(dimmed, fill(Rgb::new(212, 212, 212)));
+        assert!(limiter.estimated_milliamps() <= 100.0);
+    }
+
+    #[test]
+    fn pixel_takes_the_rest_of_the_display_into_account() {
+        let mut limiter = PowerLimiter::new(BUDGET, 2, 2);
+        let top_row = Message::Rectangle {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            red: 255,
+            green: 255,
+            blue: 255,
+        };
+        assert_eq!(limit(&mut limiter, top_row.clone()), top_row);
+        let pixel = |x| Message::Pixel {
+            x,
+            y: 1,
+            red: 255,
+            green: 255,
+            blue: 255,
+        };
+        assert_eq!(limit(&mut limiter, pixel(0)), pixel(0));
+        assert_eq!(
+            limit(&mut limiter, pixel(1)),
+            Message::Pixel {
+                x: 1,
+                y: 1,
+                red: 85,
+                green: 85,
+                blue: 85,
+            }
+        );
+        assert!((limiter.estimated_milliamps() - 100.0).abs() < 0.01);
+
+        // Darkening pixels frees the budget again
+        limit(&mut limiter, fill(Rgb::BLACK));
+        assert!(limiter.estimated_milliamps().abs() < 0.01);
+        assert_eq!(limit(&mut limiter, pixel(1)), pixel(1));
+    }
+
+    #[test]
+    fn frame_is_dimmed_by_one_factor() {
+        let budget = PowerBudget::new(50.0)
+            .milliamps_per_channel(10.0)
+            .idle_milliamps_per_pixel(0.0);
+        let mut limiter = PowerLimiter::new(budget, 2, 2);
+        let row = |y, red, green| Message::Rectangle {
+            x: 0,
+            y,
+            width: 2,
+            height: 1,
+            red,
+            green,
+            blue: 0,
+        };
+        let mut messages = [Cow::Owned(row(0, 255, 0)), Cow::Owned(row(1, 255, 255))];
+        let delta = limiter.limit(&mut messages);
+        limiter.commit(delta);
+        // 6 full channels for a budget of 5
+        assert_eq!(
+            messages.map(Cow::into_owned),
+            [row(0, 212, 0), row(1, 212, 212)]
+        );
+        assert!(limiter.estimated_milliamps() <= 50.0);
+    }
+
+    #[test]
+    fn overwritten_pixels_are_not_counted_twice() {
+        let mut limiter = PowerLimiter::new(BUDGET, 2, 2);
+        let white = fill(Rgb::WHITE);
+        let red = fill(Rgb::new(255, 0, 0));
+        let mut messages = [Cow::Borrowed(&white), Cow::Borrowed(&red)];
+        let delta = limiter.limit(&mut messages);
+        limiter.commit(delta);
+        // Only the red fill stays on the display which is within the budget
+        assert!(matches!(messages, [Cow::Borrowed(_), Cow::Borrowed(_)]));
+        assert!((limiter.estimated_milliamps() - 40.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn contiguous_is_tracked() {
+        let mut limiter = PowerLimiter::new(BUDGET, 2, 2);
+        let message = Message::Contiguous {
+            x: 1,
+            y: 0,
+            width: 1,
+            height: 2,
+            colors: vec![255, 0, 0, 0, 255, 0],
+        };
+        assert_eq!(limit(&mut limiter, message.clone()), message);
+        assert!((limiter.estimated_milliamps() - 20.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,696 @@
+//! Transport-agnostic wire format of the espPixelmatrix.
+//!
+//! After connecting the server sends a 3 byte [`Handshake`] (protocol version, width, height).
+//! Afterwards the client sends [`Message`]s which each start with their [`Command`] byte.
+
+use std::borrow::Cow;
+use std::io::{ErrorKind, Read};
+
+use crate::color::Rgb;
+use crate::error::Error;
+
+/// The only protocol version currently spoken by the espPixelmatrix.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum Command {
+    Fill = 1,
+    Pixel = 2,
+    Rectangle = 3,
+    Contiguous = 4,
+}
+
+impl TryFrom<u8> for Command {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Fill),
+            2 => Ok(Self::Pixel),
+            3 => Ok(Self::Rectangle),
+            4 => Ok(Self::Contiguous),
+            _ => Err(Error::UnknownCommand(value)),
+        }
+    }
+}
+
+/// Sent by the server directly after the connection is established.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handshake {
+    pub width: u8,
+    pub height: u8,
+}
+
+impl Handshake {
+    pub const LENGTH: usize = 3;
+
+    #[must_use]
+    pub const fn encode(self) -> [u8; Self::LENGTH] {
+        [PROTOCOL_VERSION, self.width, self.height]
+    }
+
+    /// Parse the handshake bytes.
+    ///
+    /// # Errors
+    /// Errors when the protocol version is not supported.
+    pub const fn decode(bytes: [u8; Self::LENGTH]) -> Result<Self, Error> {
+        let [protocol_version, width, height] = bytes;
+        if protocol_version != PROTOCOL_VERSION {
+            return Err(Error::UnsupportedProtocolVersion(protocol_version));
+        }
+        Ok(Self { width, height })
+    }
+
+    /// Read the handshake from a byte stream.
+    ///
+    /// # Errors
+    /// Errors when the stream could not be read or the protocol version is not supported.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut bytes = [0; Self::LENGTH];
+        reader.read_exact(&mut bytes)?;
+        Self::decode(bytes)
+    }
+}
+
+/// One command sent from the client to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Fill the whole matrix with one color.
+    Fill { red: u8, green: u8, blue: u8 },
+    /// Set one pixel of the matrix to the given color.
+    Pixel {
+        x: u8,
+        y: u8,
+        red: u8,
+        green: u8,
+        blue: u8,
+    },
+    /// Fill the given rectangular area with one color.
+    Rectangle {
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        red: u8,
+        green: u8,
+        blue: u8,
+    },
+    /// An area full of colors.
+    ///
+    /// The area begins in the top left at x/y and moves first on the x axis, then on the y axis.
+    /// The colors are given in R G B order.
+    Contiguous {
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        colors: Vec<u8>,
+    },
+}
+
+impl Message {
+    #[must_use]
+    pub const fn command(&self) -> Command {
+        match self {
+            Self::Fill { .. } => Command::Fill,
+            Self::Pixel { .. } => Command::Pixel,
+            Self::Rectangle { .. } => Command::Rectangle,
+            Self::Contiguous { .. } => Command::Contiguous,
+        }
+    }
+
+    /// Amount of bytes this message takes on the wire.
+    #[must_use]
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Self::Fill { .. } => 4,
+            Self::Pixel { .. } => 6,
+            Self::Rectangle { .. } => 8,
+            Self::Contiguous { colors, .. } => 5 + colors.len(),
+        }
+    }
+
+    /// Append the wire representation of this message to the given buffer.
+    pub fn encode<B: Extend<u8>>(&self, buffer: &mut B) {
+        let command = self.command() as u8;
+        match *self {
+            Self::Fill { red, green, blue } => buffer.extend([command, red, green, blue]),
+            Self::Pixel {
+                x,
+                y,
+                red,
+                green,
+                blue,
+            } => buffer.extend([command, x, y, red, green, blue]),
+            Self::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => buffer.extend([command, x, y, width, height, red, green, blue]),
+            Self::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => {
+                buffer.extend([command, x, y, width, height]);
+                buffer.extend(colors.iter().copied());
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.encoded_len());
+        self.encode(&mut buffer);
+        buffer
+    }
+
+    /// Decode one message from the beginning of the given bytes.
+    ///
+    /// Returns the message and the amount of bytes it used.
+    /// Returns `None` when the bytes do not yet contain a complete message.
+    ///
+    /// # Errors
+    /// Errors when the bytes do not start with a known command.
+    pub fn decode(bytes: &[u8]) -> Result<Option<(Self, usize)>, Error> {
+        let Some(&command) = bytes.first() else {
+            return Ok(None);
+        };
+        let message = match Command::try_from(command)? {
+            Command::Fill => match *bytes {
+                [_, red, green, blue, ..] => Self::Fill { red, green, blue },
+                _ => return Ok(None),
+            },
+            Command::Pixel => match *bytes {
+                [_, x, y, red, green, blue, ..] => Self::Pixel {
+                    x,
+                    y,
+                    red,
+                    green,
+                    blue,
+                },
+                _ => return Ok(None),
+            },
+            Command::Rectangle => match *bytes {
+                [_, x, y, width, height, red, green, blue, ..] => Self::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    red,
+                    green,
+                    blue,
+                },
+                _ => return Ok(None),
+            },
+            Command::Contiguous => match *bytes {
+                [_, x, y, width, height, ref rest @ ..] => {
+                    let length = contiguous_colors_length(width, height);
+                    let Some(colors) = rest.get(..length) else {
+                        return Ok(None);
+                    };
+                    Self::Contiguous {
+                        x,
+                        y,
+                        width,
+                        height,
+                        colors: colors.to_vec(),
+                    }
+                }
+                _ => return Ok(None),
+            },
+        };
+        let used = message.encoded_len();
+        Ok(Some((message, used)))
+    }
+
+    /// Read the next message from a byte stream.
+    ///
+    /// Returns `None` when the stream ended cleanly before a new message began.
+    ///
+    /// # Errors
+    /// Errors when the stream could not be read, ended within a message or contains an unknown command.
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Option<Self>, Error> {
+        let mut command = [0; 1];
+        match reader.read_exact(&mut command) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error.into()),
+        }
+        let message = match Command::try_from(command[0])? {
+            Command::Fill => {
+                let mut buf = [0; 3];
+                reader.read_exact(&mut buf)?;
+                let [red, green, blue] = buf;
+                Self::Fill { red, green, blue }
+            }
+            Command::Pixel => {
+                let mut buf = [0; 5];
+                reader.read_exact(&mut buf)?;
+                let [x, y, red, green, blue] = buf;
+                Self::Pixel {
+                    x,
+                    y,
+                    red,
+                    green,
+                    blue,
+                }
+            }
+            Command::Rectangle => {
+                let mut buf = [0; 7];
+                reader.read_exact(&mut buf)?;
+                let [x, y, width, height, red, green, blue] = buf;
+                Self::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    red,
+                    green,
+                    blue,
+                }
+            }
+            Command::Contiguous => {
+                let mut buf = [0; 4];
+                reader.read_exact(&mut buf)?;
+                let [x, y, width, height] = buf;
+                let mut colors = vec![0; contiguous_colors_length(width, height)];
+                reader.read_exact(&mut colors)?;
+                Self::Contiguous {
+                    x,
+                    y,
+                    width,
+                    height,
+                    colors,
+                }
+            }
+        };
+        Ok(Some(message))
+    }
+
+    /// Replace every color of the message.
+    pub fn map_colors(&mut self, mut map: impl FnMut(Rgb) -> Rgb) {
+        match self {
+            Self::Fill { red, green, blue }
+            | Self::Pixel {
+                red, green, blue, ..
+            }
+            | Self::Rectangle {
+                red, green, blue, ..
+            } => {
+                let color = map(Rgb::new(*red, *green, *blue));
+                (*red, *green, *blue) = (color.red, color.green, color.blue);
+            }
+            Self::Contiguous { colors, .. } => {
+                for chunk in colors.chunks_exact_mut(3) {
+                    let color = map(Rgb::new(chunk[0], chunk[1], chunk[2]));
+                    chunk.copy_from_slice(&color.to_array());
+                }
+            }
+        }
+    }
+
+    /// Check that the message fits on a display of the given size.
+    ///
+    /// # Errors
+    /// Errors when the area is not fully on the display or the amount of colors does not match the area.
+    pub fn validate(&self, display_width: u8, display_height: u8) -> Result<(), Error> {
+        let (x, y, width, height) = match *self {
+            Self::Fill { .. } => return Ok(()),
+            Self::Pixel { x, y, .. } => (x, y, 1, 1),
+            Self::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => (x, y, width, height),
+            Self::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => {
+                let expected = contiguous_colors_length(width, height);
+                if expected != colors.len() {
+                    return Err(Error::ColorBufferLength {
+                        expected,
+                        actual: colors.len(),
+                    });
+                }
+                (x, y, width, height)
+            }
+        };
+        let too_wide = x.checked_add(width).is_none_or(|end| end > display_width);
+        let too_high = y.checked_add(height).is_none_or(|end| end > display_height);
+        if too_wide || too_high {
+            return Err(Error::OutOfBounds {
+                x: x.into(),
+                y: y.into(),
+                width: width.into(),
+                height: height.into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Cut away everything outside of a display of the given size.
+    ///
+    /// Returns `None` when nothing of the message is on the display.
+    /// The colors of a [`Message::Contiguous`] have to match its area, see [`validate`](Self::validate).
+    #[must_use]
+    pub fn clipped(&self, display_width: u8, display_height: u8) -> Option<Self> {
+        let clip = |x: u8, y: u8, width: u8, height: u8| {
+            let x_end = x.saturating_add(width).min(display_width);
+            let y_end = y.saturating_add(height).min(display_height);
+            (x < x_end && y < y_end).then(|| (x_end - x, y_end - y))
+        };
+        match *self {
+            Self::Fill { .. } => Some(self.clone()),
+            Self::Pixel { x, y, .. } => clip(x, y, 1, 1).map(|_| self.clone()),
+            Self::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => {
+                let (width, height) = clip(x, y, width, height)?;
+                Some(Self::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    red,
+                    green,
+                    blue,
+                })
+            }
+            Self::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => {
+                let (clipped_width, clipped_height) = clip(x, y, width, height)?;
+                let row_length = contiguous_colors_length(width, 1);
+                let kept_length = contiguous_colors_length(clipped_width, 1);
+                let colors = colors
+                    .chunks_exact(row_length)
+                    .take(clipped_height.into())
+                    .flat_map(|row| &row[..kept_length])
+                    .copied()
+                    .collect();
+                Some(Self::Contiguous {
+                    x,
+                    y,
+                    width: clipped_width,
+                    height: clipped_height,
+                    colors,
+                })
+            }
+        }
+    }
+}
+
+/// Amount of color bytes following a [`Command::Contiguous`] header.
+#[must_use]
+pub const fn contiguous_colors_length(width: u8, height: u8) -> usize {
+    (width as usize) * (height as usize) * 3
+}
+
+/// How the clients handle messages which do not fit on the display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Bounds {
+    /// Fail with [`Error::OutOfBounds`] without sending anything.
+    #[default]
+    Reject,
+    /// Only send the part which is on the display. Messages completely outside are dropped.
+    Clip,
+}
+
+impl Bounds {
+    /// The message to be sent to a display of the given size.
+    ///
+    /// Returns `None` when there is nothing to send.
+    ///
+    /// # Errors
+    /// Errors when the message does not fit and should be rejected or the amount of colors does not match the area.
+    pub fn apply(
+        self,
+        message: &Message,
+        display_width: u8,
+        display_height: u8,
+    ) -> Result<Option<Cow<'_, Message>>, Error> {
+        match message.validate(display_width, display_height) {
+            Ok(()) => Ok(Some(Cow::Borrowed(message))),
+            Err(Error::OutOfBounds { .. }) if self == Self::Clip => Ok(message
+                .clipped(display_width, display_height)
+                .map(Cow::Owned)),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn examples() -> Vec<Message> {
+        vec![
+            Message::Fill {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            Message::Pixel {
+                x: 4,
+                y: 5,
+                red: 6,
+                green: 7,
+                blue: 8,
+            },
+            Message::Rectangle {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+                red: 5,
+                green: 6,
+                blue: 7,
+            },
+            Message::Contiguous {
+                x: 1,
+                y: 0,
+                width: 2,
+                height: 1,
+                colors: vec![10, 11, 12, 13, 14, 15],
+            },
+        ]
+    }
+
+    #[test]
+    fn encode_layout() {
+        let bytes = examples()
+            .iter()
+            .flat_map(Message::to_bytes)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bytes,
+            [
+                1, 1, 2, 3, //
+                2, 4, 5, 6, 7, 8, //
+                3, 1, 2, 3, 4, 5, 6, 7, //
+                4, 1, 0, 2, 1, 10, 11, 12, 13, 14, 15,
+            ]
+        );
+    }
+
+    #[test]
+    fn encoded_len_matches() {
+        for message in examples() {
+            assert_eq!(message.encoded_len(), message.to_bytes().len());
+        }
+    }
+
+    #[test]
+    fn decode_roundtrip() {
+        for message in examples() {
+            let bytes = message.to_bytes();
+            let decoded = Message::decode(&bytes).unwrap();
+            assert_eq!(decoded, Some((message, bytes.len())));
+        }
+    }
+
+    #[test]
+    fn decode_incomplete() {
+        for message in examples() {
+            let bytes = message.to_bytes();
+            for end in 0..bytes.len() {
+                assert_eq!(Message::decode(&bytes[..end]).unwrap(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_unknown_command() {
+        assert!(matches!(
+            Message::decode(&[42, 0, 0, 0]),
+            Err(Error::UnknownCommand(42))
+        ));
+    }
+
+    #[test]
+    fn read_stream() {
+        let examples = examples();
+        let bytes = examples
+            .iter()
+            .flat_map(Message::to_bytes)
+            .collect::<Vec<_>>();
+        let mut reader = bytes.as_slice();
+        for expected in examples {
+            assert_eq!(Message::read_from(&mut reader).unwrap(), Some(expected));
+        }
+        assert_eq!(Message::read_from(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn handshake_roundtrip() {
+        let handshake = Handshake {
+            width: 32,
+            height: 8,
+        };
+        assert_eq!(handshake.encode(), [1, 32, 8]);
+        let mut reader = handshake.encode();
+        assert_eq!(
+            Handshake::read_from(&mut reader.as_slice()).unwrap(),
+            handshake
+        );
+        reader[0] = 2;
+        assert!(matches!(
+            Handshake::decode(reader),
+            Err(Error::UnsupportedProtocolVersion(2))
+        ));
+    }
+
+    #[test]
+    fn validate_bounds() {
+        for message in examples() {
+            assert!(message.validate(10, 10).is_ok(), "{message:?}");
+        }
+        let pixel = Message::Pixel {
+            x: 4,
+            y: 2,
+            red: 1,
+            green: 1,
+            blue: 1,
+        };
+        assert!(pixel.validate(5, 3).is_ok());
+        assert!(matches!(
+            pixel.validate(4, 3),
+            Err(Error::OutOfBounds {
+                x: 4,
+                y: 2,
+                width: 1,
+                height: 1
+            })
+        ));
+        let rectangle = Message::Rectangle {
+            x: 200,
+            y: 0,
+            width: 100,
+            height: 1,
+            red: 1,
+            green: 1,
+            blue: 1,
+        };
+        assert!(matches!(
+            rectangle.validate(255, 255),
+            Err(Error::OutOfBounds { .. })
+        ));
+        let contiguous = Message::Contiguous {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            colors: vec![0; 5],
+        };
+        assert!(matches!(
+            contiguous.validate(255, 255),
+            Err(Error::ColorBufferLength {
+                expected: 6,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn clip_contiguous() {
+        let message = Message::Contiguous {
+            x: 2,
+            y: 1,
+            width: 2,
+            height: 3,
+            colors: vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6],
+        };
+        assert_eq!(
+            message.clipped(3, 3),
+            Some(Message::Contiguous {
+                x: 2,
+                y: 1,
+                width: 1,
+                height: 2,
+                colors: vec![1, 1, 1, 3, 3, 3],
+            })
+        );
+        assert_eq!(message.clipped(2, 3), None);
+        assert_eq!(message.clipped(10, 10), Some(message));
+    }
+
+    #[test]
+    fn bounds_apply() {
+        let inside = Message::Rectangle {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2,
+            red: 1,
+            green: 2,
+            blue: 3,
+        };
+        let outside = Message::Rectangle {
+            x: 1,
+            y: 1,
+            width: 200,
+            height: 2,
+            red: 1,
+            green: 2,
+            blue: 3,
+        };
+        assert!(matches!(
+            Bounds::Reject.apply(&inside, 3, 3),
+            Ok(Some(Cow::Borrowed(_)))
+        ));
+        assert!(matches!(
+            Bounds::Reject.apply(&outside, 3, 3),
+            Err(Error::OutOfBounds { width: 200, .. })
+        ));
+        assert_eq!(
+            Bounds::Clip.apply(&outside, 3, 3).unwrap().as_deref(),
+            Some(&inside)
+        );
+        assert!(Bounds::Clip.apply(&outside, 1, 3).unwrap().is_none());
+    }
+}
@@ -0,0 +1,143 @@
+use core::time::Duration;
+use std::io::ErrorKind;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
+
+use bufstream::BufStream;
+
+use crate::correction::Correction;
+use crate::error::Error;
+use crate::pipeline::Pipeline;
+use crate::power::PowerBudget;
+use crate::protocol::{Bounds, Handshake};
+use crate::sync::Client;
+
+/// Configure how a [`Client`] connects and talks to the server.
+///
+/// Every timeout is disabled by default.
+/// Commands which do not fit on the display are rejected by default.
+/// An elapsed timeout results in an error of kind [`ErrorKind::TimedOut`],
+/// a refused connection in [`ErrorKind::ConnectionRefused`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct ClientBuilder {
+    connect_timeout: Option<Duration>,
+    handshake_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    bounds: Bounds,
+    correction: Option<Correction>,
+    power_budget: Option<PowerBudget>,
+}
+
+impl ClientBuilder {
+    pub const fn new() -> Self {
+        Self {
+            connect_timeout: None,
+            handshake_timeout: None,
+            write_timeout: None,
+            bounds: Bounds::Reject,
+            correction: None,
+            power_budget: None,
+        }
+    }
+
+    /// Timeout for establishing the TCP connection.
+    ///
+    /// For each `SocketAddr` possible from the given `addr` the timeout is used.
+    /// When an `addr` resolved to IPv4 and IPv6 the timeout could be used up twice.
+    pub const fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for receiving the protocol version and display size after connecting.
+    pub const fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each write to the server. Relevant when flushing.
+    pub const fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// How to handle drawing commands which do not fit on the display. Defaults to [`Bounds::Reject`].
+    pub const fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Correct every color before sending it. See [`Correction`].
+    pub const fn correction(mut self, correction: Correction) -> Self {
+        self.correction = Some(correction);
+        self
+    }
+
+    /// Dim colors which would make the display exceed the [`PowerBudget`].
+    pub const fn power_budget(mut self, budget: PowerBudget) -> Self {
+        self.power_budget = Some(budget);
+        self
+    }
+
+    /// Connect to the server
+    ///
+    /// # Errors
+    /// Errors when the connection could not be established.
+    pub fn connect<Address: ToSocketAddrs>(&self, address: Address) -> Result<Client, Error> {
+        let Some(timeout) = self.connect_timeout else {
+            let stream = TcpStream::connect(address)?;
+            return self.handshake(stream);
+        };
+
+        let mut last_err = None;
+        for address in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, timeout)
+                .map_err(Error::from)
+                .and_then(|stream| self.handshake(stream))
+            {
+                Ok(client) => return Ok(client),
+                Err(error) => last_err = Some(error),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            std::io::Error::new(ErrorKind::InvalidInput, "could not resolve to any address").into()
+        }))
+    }
+
+    fn handshake(&self, stream: TcpStream) -> Result<Client, Error> {
+        stream.set_read_timeout(self.handshake_timeout)?;
+        stream.set_write_timeout(self.write_timeout)?;
+        let mut stream = BufStream::new(stream);
+        let Handshake { width, height } =
+            Handshake::read_from(&mut stream).map_err(|error| match error {
+                Error::Io(error) => timed_out(error, "handshake").into(),
+                error => error,
+            })?;
+
+        Ok(Client {
+            stream: Arc::new(Mutex::new(Some(stream))),
+            width,
+            height,
+            bytes_sent: Arc::new(AtomicU64::new(0)),
+            pipeline: Pipeline::new(
+                width,
+                height,
+                self.bounds,
+                self.correction.clone(),
+                self.power_budget,
+            ),
+        })
+    }
+}
+
+/// Socket timeouts are reported as [`ErrorKind::WouldBlock`] on some platforms.
+/// Report them consistently as [`ErrorKind::TimedOut`].
+pub(super) fn timed_out(error: std::io::Error, operation: &str) -> std::io::Error {
+    if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) {
+        std::io::Error::new(ErrorKind::TimedOut, format!("{operation} timed out"))
+    } else {
+        error
+    }
+}
@@ -0,0 +1,219 @@
+use crate::canvas::clip;
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::font::TextStyle;
+use crate::sync::{Client, TiledDisplay, TransformedClient};
+
+/// Draw with coordinates beyond the `u8` of the protocol.
+///
+/// Everything outside of the display is clipped away before it is sent,
+/// so content can be placed partially or completely off-screen, for example while scrolling it in.
+///
+/// Do not forget to also run `flush` afterwards.
+pub trait Canvas {
+    /// Width and height of the drawable area.
+    fn size(&self) -> (u16, u16);
+
+    /// Fill an area within [`size`](Self::size) with one color.
+    ///
+    /// # Errors
+    /// Errors when the area does not fit on the display or the command could not be sent
+    fn fill_area(&self, x: u16, y: u16, width: u16, height: u16, color: Rgb) -> Result<(), Error>;
+
+    /// Send the colors of an area within [`size`](Self::size).
+    ///
+    /// # Errors
+    /// Errors when the area does not fit on the display or the command could not be sent
+    fn write_area(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        colors: &[Rgb],
+    ) -> Result<(), Error>;
+
+    /// Set one pixel to the given color. Nothing is sent when it is off-screen.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    fn draw_pixel(&self, x: i32, y: i32, color: Rgb) -> Result<(), Error> {
+        self.draw_rectangle(x, y, 1, 1, color)
+    }
+
+    /// Fill the visible part of the given rectangular area with one color.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    fn draw_rectangle(
+        &self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        color: Rgb,
+    ) -> Result<(), Error> {
+        let (display_width, display_height) = self.size();
+        let Some(visible) = clip(x, y, width, height, display_width, display_height) else {
+            return Ok(());
+        };
+        self.fill_area(visible.x, visible.y, visible.width, visible.height, color)
+    }
+
+    /// Send the visible part of an area full of colors.
+    ///
+    /// The area begins in the top left at x/y and moves first on the x axis, then on the y axis.
+    ///
+    /// # Errors
+    /// Errors when the amount of colors does not match the area or the command could not be sent
+    fn draw_contiguous(
+        &self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        let expected = u64::from(width) * u64::from(height);
+        if u64::try_from(colors.len()).ok() != Some(expected) {
+            return Err(Error::ColorBufferLength {
+                expected: usize::try_from(expected).unwrap_or(usize::MAX / 3) * 3,
+                actual: colors.len() * 3,
+            });
+        }
+        let (display_width, display_height) = self.size();
+        let Some(visible) = clip(x, y, width, height, display_width, display_height) else {
+            return Ok(());
+        };
+        self.write_area(
+            visible.x,
+            visible.y,
+            visible.width,
+            visible.height,
+            &visible.colors(colors, width),
+        )
+    }
+
+    /// Draw the text with the top left corner of its [layout](TextStyle::layout) at x/y.
+    ///
+    /// With a background the whole area is sent at once, otherwise only the pixels of the characters.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    fn draw_text(&self, x: i32, y: i32, text: &str, style: &TextStyle) -> Result<(), Error> {
+        let layout = style.layout(text);
+        if layout.is_opaque() {
+            let colors = layout
+                .pixels()
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>();
+            return self.draw_contiguous(x, y, layout.width(), layout.height(), &colors);
+        }
+        for (run_x, run_y, length, color) in layout.runs() {
+            self.draw_rectangle(
+                x.saturating_add_unsigned(run_x),
+                y.saturating_add_unsigned(run_y),
+                length,
+                1,
+                color,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn narrow(x: u16, y: u16, width: u16, height: u16) -> Result<(u8, u8, u8, u8), Error> {
+    let out_of_bounds = |_| Error::OutOfBounds {
+        x,
+        y,
+        width,
+        height,
+    };
+    Ok((
+        u8::try_from(x).map_err(out_of_bounds)?,
+        u8::try_from(y).map_err(out_of_bounds)?,
+        u8::try_from(width).map_err(out_of_bounds)?,
+        u8::try_from(height).map_err(out_of_bounds)?,
+    ))
+}
+
+impl Canvas for Client {
+    fn size(&self) -> (u16, u16) {
+        (self.width().into(), self.height().into())
+    }
+
+    fn fill_area(&self, x: u16, y: u16, width: u16, height: u16, color: Rgb) -> Result<(), Error> {
+        let (x, y, width, height) = narrow(x, y, width, height)?;
+        if width == 1 && height == 1 {
+            self.pixel(x, y, color)
+        } else {
+            self.rectangle(x, y, width, height, color)
+        }
+    }
+
+    fn write_area(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        let (x, y, width, height) = narrow(x, y, width, height)?;
+        self.contiguous(x, y, width, height, colors)
+    }
+}
+
+impl Canvas for TransformedClient {
+    fn size(&self) -> (u16, u16) {
+        (self.width().into(), self.height().into())
+    }
+
+    fn fill_area(&self, x: u16, y: u16, width: u16, height: u16, color: Rgb) -> Result<(), Error> {
+        let (x, y, width, height) = narrow(x, y, width, height)?;
+        if width == 1 && height == 1 {
+            self.pixel(x, y, color)
+        } else {
+            self.rectangle(x, y, width, height, color)
+        }
+    }
+
+    fn write_area(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        let (x, y, width, height) = narrow(x, y, width, height)?;
+        self.contiguous(x, y, width, height, colors)
+    }
+}
+
+impl Canvas for TiledDisplay {
+    fn size(&self) -> (u16, u16) {
+        (self.width(), self.height())
+    }
+
+    fn fill_area(&self, x: u16, y: u16, width: u16, height: u16, color: Rgb) -> Result<(), Error> {
+        if width == 1 && height == 1 {
+            self.pixel(x, y, color)
+        } else {
+            self.rectangle(x, y, width, height, color)
+        }
+    }
+
+    fn write_area(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        self.contiguous(x, y, width, height, colors)
+    }
+}
@@ -0,0 +1,149 @@
+use core::ops::ControlFlow;
+use std::thread::sleep;
+use std::time::Instant;
+
+use crate::error::Error;
+use crate::pacing::{Frame, FrameStats, Pacer};
+use crate::sync::{Client, DoubleBuffer, ReconnectingClient, TiledDisplay, TransformedClient};
+
+/// Display which the [`FrameLoop`] flushes after every frame.
+pub trait FrameTarget {
+    /// Send everything drawn for the current frame.
+    ///
+    /// # Errors
+    /// Errors when the commands could not be sent
+    fn flush_frame(&mut self) -> Result<(), Error>;
+
+    /// Amount of bytes written to the connection so far.
+    fn bytes_sent(&self) -> u64;
+}
+
+/// Render at a target frame rate, see [`Pacer`].
+///
+/// The time spent rendering and sending is taken into account.
+/// When a frame took too long the missed frames are skipped.
+///
+/// ```no_run
+/// # use core::ops::ControlFlow;
+/// # use esp_remotecontrolled_led_matrix_client::Rgb;
+/// # use esp_remotecontrolled_led_matrix_client::sync::{Client, FrameLoop};
+/// # fn main() -> Result<(), esp_remotecontrolled_led_matrix_client::Error> {
+/// let mut client = Client::connect("espPixelmatrix:1337")?;
+/// let mut frame_loop = FrameLoop::new(30.0);
+/// frame_loop.run(&mut client, |client, frame| {
+///     let hue = frame.elapsed.as_secs_f32() * 90.0;
+///     client.fill(Rgb::from_hsv(hue % 360.0, 1.0, 1.0))?;
+///     Ok(ControlFlow::Continue(()))
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameLoop {
+    pacer: Pacer,
+}
+
+impl FrameLoop {
+    #[must_use]
+    pub fn new(fps: f32) -> Self {
+        Self {
+            pacer: Pacer::new(fps),
+        }
+    }
+
+    #[must_use]
+    pub const fn stats(&self) -> &FrameStats {
+        self.pacer.stats()
+    }
+
+    /// Render frames until `render` returns [`ControlFlow::Break`].
+    ///
+    /// # Errors
+    /// Errors when `render` errors or the frame could not be sent
+    pub fn run<Target: FrameTarget>(
+        &mut self,
+        target: &mut Target,
+        mut render: impl FnMut(&mut Target, Frame) -> Result<ControlFlow<()>, Error>,
+    ) -> Result<(), Error> {
+        while self.frame(target, &mut render)?.is_continue() {}
+        Ok(())
+    }
+
+    /// Render and flush a single frame, then wait until the next one is due.
+    ///
+    /// # Errors
+    /// Errors when `render` errors or the frame could not be sent
+    pub fn frame<Target: FrameTarget>(
+        &mut self,
+        target: &mut Target,
+        render: impl FnOnce(&mut Target, Frame) -> Result<ControlFlow<()>, Error>,
+    ) -> Result<ControlFlow<()>, Error> {
+        let frame = self.pacer.begin(Instant::now());
+        let bytes_before = target.bytes_sent();
+        let flow = render(target, frame)?;
+        let flush_start = Instant::now();
+        target.flush_frame()?;
+        let now = Instant::now();
+        let bytes = target.bytes_sent().saturating_sub(bytes_before);
+        let wait = self.pacer.finish(now, bytes, now - flush_start);
+        if flow.is_continue() {
+            sleep(wait);
+        }
+        Ok(flow)
+    }
+}
+
+impl FrameTarget for Client {
+    fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        Self::bytes_sent(self)
+    }
+}
+
+/// Every frame is [presented](DoubleBuffer::present).
+impl FrameTarget for DoubleBuffer {
+    fn flush_frame(&mut self) -> Result<(), Error> {
+        self.present()
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
+
+impl FrameTarget for TransformedClient {
+    fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
+
+impl FrameTarget for TiledDisplay {
+    fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.clients()
+            .iter()
+            .map(|client| client.client().bytes_sent())
+            .sum()
+    }
+}
+
+/// The amount of bytes starts over with every reconnect.
+impl FrameTarget for ReconnectingClient {
+    fn flush_frame(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+
+    fn bytes_sent(&self) -> u64 {
+        self.client().bytes_sent()
+    }
+}
@@ -0,0 +1,187 @@
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::protocol::Bounds;
+use crate::sync::{ClientBuilder, TransformedClient};
+use crate::tiling::{Layout, Tile};
+
+/// Several displays arranged in a grid acting as one large display.
+///
+/// Commands are split across the displays. Coordinates can exceed 255.
+/// Areas within the grid without a display (when the tiles differ in size) are ignored.
+pub struct TiledDisplay {
+    clients: Vec<TransformedClient>,
+    layout: Layout,
+    bounds: Bounds,
+}
+
+impl TiledDisplay {
+    /// Connect to every tile.
+    ///
+    /// # Errors
+    /// Errors when two tiles share the same column and row or one of the connections could not be established.
+    pub fn connect(tiles: &[Tile]) -> Result<Self, Error> {
+        Self::connect_with_builder(tiles, &ClientBuilder::new())
+    }
+
+    /// Connect to every tile with the settings of the [`ClientBuilder`].
+    ///
+    /// The [`Bounds`] of the builder also apply to the combined display.
+    ///
+    /// # Errors
+    /// Errors when two tiles share the same column and row or one of the connections could not be established.
+    pub fn connect_with_builder(tiles: &[Tile], builder: &ClientBuilder) -> Result<Self, Error> {
+        let clients = tiles
+            .iter()
+            .map(|tile| {
+                let client = builder.connect(tile.address.as_str())?;
+                Ok(TransformedClient::new(client, tile.transform()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let sizes = clients
+            .iter()
+            .map(|client| (client.width(), client.height()))
+            .collect::<Vec<_>>();
+        let bounds = clients
+            .first()
+            .map_or(Bounds::Reject, |client| client.client().bounds());
+        Ok(Self {
+            clients,
+            layout: Layout::new(tiles, &sizes)?,
+            bounds,
+        })
+    }
+
+    /// The clients of each tile in the order of the tiles.
+    #[must_use]
+    pub fn clients(&self) -> &[TransformedClient] {
+        &self.clients
+    }
+
+    #[must_use]
+    pub const fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u16 {
+        self.layout.width()
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u16 {
+        self.layout.height()
+    }
+
+    /// Flush every tile so they show the new frame at about the same time.
+    ///
+    /// The other tiles are still flushed when one of them fails.
+    ///
+    /// # Errors
+    /// Errors with the first error when the commands could not be sent to one of the tiles
+    pub fn flush(&self) -> Result<(), Error> {
+        let mut first_error = None;
+        for client in &self.clients {
+            if let Err(error) = client.flush() {
+                first_error.get_or_insert(error);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn check_bounds(&self, x: u16, y: u16, width: u16, height: u16) -> Result<(), Error> {
+        if self.bounds == Bounds::Reject && !self.layout.contains(x, y, width, height) {
+            return Err(Error::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+        Ok(())
+    }
+
+    /// Set one pixel to the given color.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the pixel is not on the display and [`Bounds::Reject`] is used
+    /// or the data could not be written to the send buffer
+    pub fn pixel(&self, x: u16, y: u16, color: Rgb) -> Result<(), Error> {
+        self.check_bounds(x, y, 1, 1)?;
+        for part in self.layout.intersections(x, y, 1, 1) {
+            self.clients[part.tile].pixel(part.x, part.y, color)?;
+        }
+        Ok(())
+    }
+
+    /// Fill every tile with one color.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    pub fn fill(&self, color: Rgb) -> Result<(), Error> {
+        for client in &self.clients {
+            client.fill(color)?;
+        }
+        Ok(())
+    }
+
+    /// Fill the given rectangular area with one color.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the area does not fit on the display and [`Bounds::Reject`] is used
+    /// or the command could not be sent
+    pub fn rectangle(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        color: Rgb,
+    ) -> Result<(), Error> {
+        self.check_bounds(x, y, width, height)?;
+        for part in self.layout.intersections(x, y, width, height) {
+            self.clients[part.tile].rectangle(part.x, part.y, part.width, part.height, color)?;
+        }
+        Ok(())
+    }
+
+    /// Send an area full of colors.
+    ///
+    /// The area begins in the top left at x/y and moves first on the x axis, then on the y axis.
+    ///
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// # Errors
+    /// Errors when the amount of colors does not match the area,
+    /// the area does not fit on the display and [`Bounds::Reject`] is used
+    /// or the command could not be sent
+    pub fn contiguous(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        let expected = usize::from(width) * usize::from(height);
+        if expected != colors.len() {
+            return Err(Error::ColorBufferLength {
+                expected: expected * 3,
+                actual: colors.len() * 3,
+            });
+        }
+        self.check_bounds(x, y, width, height)?;
+        for part in self.layout.intersections(x, y, width, height) {
+            self.clients[part.tile].contiguous(
+                part.x,
+                part.y,
+                part.width,
+                part.height,
+                &part.colors(colors, width),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::framebuffer::Framebuffer;
+use crate::protocol::Message;
+use crate::sync::Client;
+use crate::transform::Transform;
+
+/// [`Client`] which draws through a [`Transform`].
+///
+/// Coordinates, sizes and [`Framebuffer`]s are given in the transformed view.
+#[derive(Clone)]
+pub struct TransformedClient {
+    client: Client,
+    transform: Transform,
+}
+
+impl TransformedClient {
+    #[must_use]
+    pub const fn new(client: Client, transform: Transform) -> Self {
+        Self { client, transform }
+    }
+
+    /// The client drawing on the physical display.
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub const fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Width of the transformed view.
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.transform
+            .size(self.client.width(), self.client.height())
+            .0
+    }
+
+    /// Height of the transformed view.
+    #[must_use]
+    pub const fn height(&self) -> u8 {
+        self.transform
+            .size(self.client.width(), self.client.height())
+            .1
+    }
+
+    #[must_use]
+    pub const fn total_pixels(&self) -> u16 {
+        (self.width() as u16) * (self.height() as u16)
+    }
+
+    /// Create a [`Framebuffer`] with the size of the transformed view.
+    #[must_use]
+    pub fn framebuffer(&self) -> Framebuffer {
+        Framebuffer::new(self.width(), self.height())
+    }
+
+    /// See [`Client::flush`]
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    pub fn flush(&self) -> Result<(), Error> {
+        self.client.flush()
+    }
+
+    /// See [`Client::present`]
+    ///
+    /// # Errors
+    /// Errors when the framebuffer size does not match the transformed view or the commands could not be sent
+    pub fn present(&self, framebuffer: &mut Framebuffer) -> Result<(), Error> {
+        if framebuffer.width() != self.width() || framebuffer.height() != self.height() {
+            return Err(Error::SizeMismatch {
+                display: (self.width(), self.height()),
+                content: (framebuffer.width(), framebuffer.height()),
+            });
+        }
+        self.send_all(&framebuffer.diff())?;
+        self.flush()?;
+        framebuffer.mark_presented();
+        Ok(())
+    }
+
+    /// Send a single [`Message`] given in the transformed view.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// Messages which do not fit on the transformed view are handled according to the [`Bounds`](crate::protocol::Bounds) of the client.
+    ///
+    /// # Errors
+    /// Errors when the message is invalid for the transformed view (see [`Message::validate`])
+    /// or the data could not be written to the send buffer
+    pub fn send(&self, message: &Message) -> Result<(), Error> {
+        self.send_all(core::slice::from_ref(message))
+    }
+
+    /// Send several [`Message`]s given in the transformed view with a single write.
+    /// Do not forget to also run [`flush`](Self::flush) afterwards.
+    ///
+    /// See [`Client::send_all`]
+    ///
+    /// # Errors
+    /// Errors when one of the messages is invalid for the transformed view (see [`Message::validate`])
+    /// or the data could not be written to the send buffer
+    pub fn send_all(&self, messages: &[Message]) -> Result<(), Error> {
+        let bounds = self.client.bounds();
+        let mut physical = Vec::with_capacity(messages.len());
+        for message in messages {
+            let Some(message) = bounds.apply(message, self.width(), self.height())? else {
+                continue;
+            };
+            physical.extend(self.transform.message(
+                &message,
+                self.client.width(),
+                self.client.height(),
+            ));
+        }
+        self.client.send_all(&physical)
+    }
+
+    /// See [`Client::pixel`]
+    ///
+    /// # Errors
+    /// Errors when the pixel is not on the transformed view and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the data could not be written to the send buffer
+    pub fn pixel(&self, x: u8, y: u8, color: Rgb) -> Result<(), Error> {
+        self.send(&Message::Pixel {
+            x,
+            y,
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+    }
+
+    /// See [`Client::fill`]
+    ///
+    /// Only the transformed view is filled when an offset is used.
+    ///
+    /// # Errors
+    /// Errors when the command could not be sent
+    pub fn fill(&self, color: Rgb) -> Result<(), Error> {
+        self.send(&Message::Fill {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+    }
+
+    /// See [`Client::rectangle`]
+    ///
+    /// # Errors
+    /// Errors when the area does not fit on the transformed view and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the command could not be sent
+    pub fn rectangle(&self, x: u8, y: u8, width: u8, height: u8, color: Rgb) -> Result<(), Error> {
+        self.send(&Message::Rectangle {
+            x,
+            y,
+            width,
+            height,
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+        })
+    }
+
+    /// See [`Client::contiguous`]
+    ///
+    /// The colors are given in the order of the transformed view and reordered for the physical display.
+    ///
+    /// # Errors
+    /// Errors when the area does not fit on the transformed view and [`Bounds::Reject`](crate::protocol::Bounds::Reject) is used
+    /// or the command could not be sent
+    pub fn contiguous(
+        &self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        colors: &[Rgb],
+    ) -> Result<(), Error> {
+        self.send(&Message::Contiguous {
+            x,
+            y,
+            width,
+            height,
+            colors: colors.iter().flat_map(|color| color.to_array()).collect(),
+        })
+    }
+}
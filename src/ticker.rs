@@ -0,0 +1,278 @@
+//! Text scrolling horizontally through the display.
+//!
+//! The clients run it with `run_ticker`. It can also be drawn into any [`Framebuffer`] for example within a `FrameLoop`.
+
+use core::time::Duration;
+
+use embedded_graphics::Drawable as _;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::text::renderer::TextRenderer as _;
+use embedded_graphics::text::{Baseline, Text};
+
+use crate::color::Rgb;
+use crate::framebuffer::Framebuffer;
+
+/// Text of any length scrolling from right to left.
+///
+/// The text consists of segments which each have their own color.
+/// It enters on the right and is vertically centered.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Ticker {
+    font: &'static MonoFont<'static>,
+    segments: Vec<(String, Rgb)>,
+    background: Rgb,
+    speed: f32,
+    looping: bool,
+    gap: u32,
+    /// Pixels scrolled since the start of the current lap
+    offset: f32,
+    /// Times the text started over when looping
+    laps: u64,
+    paused: bool,
+}
+
+impl Ticker {
+    /// Empty ticker scrolling 20 pixels per second on black, looping with a gap of 8 pixels.
+    pub const fn new(font: &'static MonoFont<'static>) -> Self {
+        Self {
+            font,
+            segments: Vec::new(),
+            background: Rgb::BLACK,
+            speed: 20.0,
+            looping: true,
+            gap: 8,
+            offset: 0.0,
+            laps: 0,
+            paused: false,
+        }
+    }
+
+    /// Append text in the given color.
+    pub fn segment(mut self, text: impl Into<String>, color: Rgb) -> Self {
+        self.push(text, color);
+        self
+    }
+
+    /// Pixels per second
+    pub const fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Start over once the text scrolled through. Otherwise the ticker is [finished](Self::is_finished) then.
+    pub const fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Pixels between the end of the text and its next start when looping.
+    pub const fn gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Append text in the given color while the ticker is running.
+    pub fn push(&mut self, text: impl Into<String>, color: Rgb) {
+        self.segments.push((text.into(), color));
+    }
+
+    /// Remove all text and start over.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.restart();
+    }
+
+    /// Let the text enter on the right again.
+    pub const fn restart(&mut self) {
+        self.offset = 0.0;
+        self.laps = 0;
+    }
+
+    /// Stop scrolling. The text stays where it is.
+    pub const fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub const fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Width of the whole text in pixels.
+    #[must_use]
+    pub fn text_width(&self) -> u32 {
+        let width = self
+            .segments
+            .iter()
+            .map(|(text, color)| {
+                MonoTextStyle::new(self.font, Rgb888::from(*color))
+                    .measure_string(text, Point::zero(), Baseline::Top)
+                    .next_position
+                    .x
+            })
+            .sum::<i32>();
+        width.unsigned_abs()
+    }
+
+    /// The text scrolled out on the left and does not loop.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn is_finished(&self, display_width: u8) -> bool {
+        !self.looping && self.offset >= (u32::from(display_width) + self.text_width()) as f32
+    }
+
+    /// Scroll further by the time passed, for example [`Frame::delta`](crate::pacing::Frame::delta).
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn advance(&mut self, delta: Duration) {
+        if self.paused {
+            return;
+        }
+        self.offset += self.speed.max(0.0) * delta.as_secs_f32();
+        let period = self.text_width() + self.gap;
+        if self.looping && period > 0 {
+            let period = period as f32;
+            let laps = (self.offset / period).floor() as u64;
+            self.offset = self.offset.rem_euclid(period);
+            self.laps = self.laps.saturating_add(laps);
+        }
+    }
+
+    /// Draw the current state over the whole framebuffer.
+    #[expect(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn draw(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.fill(self.background.to_array());
+        let text_width = self.text_width() as i32;
+        if text_width == 0 {
+            return;
+        }
+        let display_width = i32::from(framebuffer.width());
+        let y = (i32::from(framebuffer.height()) - self.font.character_size.height as i32) / 2;
+        let mut x = display_width - self.offset as i32;
+        if !self.looping {
+            self.draw_text(framebuffer, x, y);
+            return;
+        }
+        // Earlier laps might still be visible on the left
+        let period = text_width + self.gap as i32;
+        let mut laps = self.laps;
+        while laps > 0 && x - period + text_width > 0 {
+            x -= period;
+            laps -= 1;
+        }
+        while x < display_width {
+            self.draw_text(framebuffer, x, y);
+            x += period;
+        }
+    }
+
+    fn draw_text(&self, framebuffer: &mut Framebuffer, x: i32, y: i32) {
+        let mut position = Point::new(x, y);
+        for (text, color) in &self.segments {
+            let style = MonoTextStyle::new(self.font, (*color).into());
+            let Ok(next) =
+                Text::with_baseline(text, position, style, Baseline::Top).draw(framebuffer);
+            position = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mono_font::ascii::FONT_5X7;
+
+    use super::*;
+
+    /// Columns of the framebuffer which contain something else than black.
+    /// The I of the font is 3 pixels wide in the middle of its 5 pixels.
+    fn lit_columns(framebuffer: &Framebuffer) -> Vec<u8> {
+        (0..framebuffer.width())
+            .filter(|&x| (0..framebuffer.height()).any(|y| framebuffer.pixel(x, y) != Some([0; 3])))
+            .collect()
+    }
+
+    #[test]
+    fn enters_on_the_right() {
+        let mut ticker = Ticker::new(&FONT_5X7)
+            .segment("I", Rgb::WHITE)
+            .speed(10.0)
+            .looping(false);
+        assert_eq!(ticker.text_width(), 5);
+        let mut framebuffer = Framebuffer::new(10, 8);
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), []);
+
+        ticker.advance(Duration::from_millis(500));
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), [6, 7, 8]);
+        assert!(!ticker.is_finished(10));
+
+        ticker.advance(Duration::from_secs(1));
+        assert!(ticker.is_finished(10));
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), []);
+    }
+
+    #[test]
+    fn segment_colors() {
+        let mut ticker = Ticker::new(&FONT_5X7)
+            .segment("I", Rgb::RED)
+            .segment("I", Rgb::BLUE)
+            .speed(1.0);
+        assert_eq!(ticker.text_width(), 10);
+        ticker.advance(Duration::from_secs(10));
+        let mut framebuffer = Framebuffer::new(10, 7);
+        ticker.draw(&mut framebuffer);
+        assert_eq!(framebuffer.pixel(2, 0), Some(Rgb::RED.to_array()));
+        assert_eq!(framebuffer.pixel(7, 0), Some(Rgb::BLUE.to_array()));
+    }
+
+    #[test]
+    fn loops_with_gap() {
+        let mut ticker = Ticker::new(&FONT_5X7)
+            .segment("I", Rgb::WHITE)
+            .speed(1.0)
+            .gap(5);
+        let mut framebuffer = Framebuffer::new(20, 8);
+        // Two copies are visible when the display is wider than text and gap
+        ticker.advance(Duration::from_secs(17));
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), [4, 5, 6, 14, 15, 16]);
+        assert!(!ticker.is_finished(20));
+
+        // The first lap scrolled out on the left
+        ticker.advance(Duration::from_secs(10));
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), [4, 5, 6, 14, 15, 16]);
+    }
+
+    #[test]
+    fn pause() {
+        let mut ticker = Ticker::new(&FONT_5X7).segment("I", Rgb::WHITE);
+        ticker.pause();
+        ticker.advance(Duration::from_secs(1));
+        assert!(ticker.is_paused());
+        let mut framebuffer = Framebuffer::new(10, 8);
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), []);
+        ticker.resume();
+        ticker.advance(Duration::from_millis(250));
+        ticker.draw(&mut framebuffer);
+        assert_eq!(lit_columns(&framebuffer), [6, 7, 8]);
+    }
+}
@@ -0,0 +1,347 @@
+//! Arrange several displays in a grid to form one large virtual display.
+//!
+//! The clients provide this as `TiledDisplay`.
+
+use crate::color::Rgb;
+use crate::error::Error;
+use crate::transform::{Rotation, Transform};
+
+/// Address and position of one display within the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct Tile {
+    pub address: String,
+    pub column: u8,
+    pub row: u8,
+    pub rotation: Rotation,
+}
+
+impl Tile {
+    pub fn new<Address: Into<String>>(address: Address, column: u8, row: u8) -> Self {
+        Self {
+            address: address.into(),
+            column,
+            row,
+            rotation: Rotation::Deg0,
+        }
+    }
+
+    /// Rotation of the content on this display, for example when it is mounted upside down.
+    pub const fn rotate(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub const fn transform(&self) -> Transform {
+        Transform::new().rotate(self.rotation)
+    }
+}
+
+/// Area of one tile within the virtual display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    pub x: u16,
+    pub y: u16,
+    pub width: u8,
+    pub height: u8,
+}
+
+/// Part of a region which is on one tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Intersection {
+    /// Index of the tile
+    pub tile: usize,
+    /// Position within the tile
+    pub x: u8,
+    /// Position within the tile
+    pub y: u8,
+    pub width: u8,
+    pub height: u8,
+    /// Position of the intersection within the region
+    pub region_x: u16,
+    /// Position of the intersection within the region
+    pub region_y: u16,
+}
+
+impl Intersection {
+    /// Take the colors of this intersection out of the colors of the whole region.
+    #[must_use]
+    pub fn colors(&self, colors: &[Rgb], region_width: u16) -> Vec<Rgb> {
+        let region_width = usize::from(region_width);
+        let x = usize::from(self.region_x);
+        let y = usize::from(self.region_y);
+        colors
+            .chunks_exact(region_width)
+            .skip(y)
+            .take(self.height.into())
+            .flat_map(|row| &row[x..x + usize::from(self.width)])
+            .copied()
+            .collect()
+    }
+}
+
+/// Where each tile is placed within the virtual display.
+///
+/// Each column is as wide as its widest tile and each row as high as its highest tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    areas: Vec<Area>,
+    width: u16,
+    height: u16,
+}
+
+impl Layout {
+    /// Arrange the tiles by their column and row.
+    /// The sizes are the ones seen through the rotation of each tile.
+    ///
+    /// # Errors
+    /// Errors when two tiles share the same column and row.
+    ///
+    /// # Panics
+    /// Panics when the amount of tiles and sizes differ.
+    pub fn new(tiles: &[Tile], sizes: &[(u8, u8)]) -> Result<Self, Error> {
+        assert_eq!(tiles.len(), sizes.len(), "every tile needs a size");
+        for (index, tile) in tiles.iter().enumerate() {
+            if tiles[..index]
+                .iter()
+                .any(|other| (other.column, other.row) == (tile.column, tile.row))
+            {
+                return Err(Error::DuplicateTile {
+                    column: tile.column,
+                    row: tile.row,
+                });
+            }
+        }
+        let mut column_widths = Vec::new();
+        let mut row_heights = Vec::new();
+        for (tile, &(width, height)) in tiles.iter().zip(sizes) {
+            let column = usize::from(tile.column);
+            let row = usize::from(tile.row);
+            if column_widths.len() <= column {
+                column_widths.resize(column + 1, 0);
+            }
+            if row_heights.len() <= row {
+                row_heights.resize(row + 1, 0);
+            }
+            column_widths[column] = column_widths[column].max(u16::from(width));
+            row_heights[row] = row_heights[row].max(u16::from(height));
+        }
+        let column_starts = starts(&column_widths);
+        let row_starts = starts(&row_heights);
+        let areas = tiles
+            .iter()
+            .zip(sizes)
+            .map(|(tile, &(width, height))| Area {
+                x: column_starts[usize::from(tile.column)],
+                y: row_starts[usize::from(tile.row)],
+                width,
+                height,
+            })
+            .collect();
+        // At most 256 columns or rows of 255 pixels each which always fits into u16
+        Ok(Self {
+            areas,
+            width: column_widths.iter().sum(),
+            height: row_heights.iter().sum(),
+        })
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u16 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    #[must_use]
+    pub fn areas(&self) -> &[Area] {
+        &self.areas
+    }
+
+    /// Whether the region is completely within the virtual display.
+    #[must_use]
+    pub fn contains(&self, x: u16, y: u16, width: u16, height: u16) -> bool {
+        u32::from(x) + u32::from(width) <= u32::from(self.width)
+            && u32::from(y) + u32::from(height) <= u32::from(self.height)
+    }
+
+    /// Split the region into the parts on each tile. Parts which are not on any tile are skipped.
+    pub fn intersections(
+        &self,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    ) -> impl Iterator<Item = Intersection> + '_ {
+        let x_end = u32::from(x) + u32::from(width);
+        let y_end = u32::from(y) + u32::from(height);
+        self.areas
+            .iter()
+            .enumerate()
+            .filter_map(move |(tile, area)| {
+                let start_x = x.max(area.x);
+                let start_y = y.max(area.y);
+                let end_x = x_end.min(u32::from(area.x) + u32::from(area.width));
+                let end_y = y_end.min(u32::from(area.y) + u32::from(area.height));
+                if u32::from(start_x) >= end_x || u32::from(start_y) >= end_y {
+                    return None;
+                }
+                // Everything is within the tile so it fits into u8
+                let narrow = |value: u32| u8::try_from(value).ok();
+                Some(Intersection {
+                    tile,
+                    x: narrow(u32::from(start_x - area.x))?,
+                    y: narrow(u32::from(start_y - area.y))?,
+                    width: narrow(end_x - u32::from(start_x))?,
+                    height: narrow(end_y - u32::from(start_y))?,
+                    region_x: start_x - x,
+                    region_y: start_y - y,
+                })
+            })
+    }
+}
+
+fn starts(lengths: &[u16]) -> Vec<u16> {
+    lengths
+        .iter()
+        .scan(0_u16, |start, length| {
+            let current = *start;
+            *start += length;
+            Some(current)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Layout {
+        // 200x8 | 200x8
+        // ------+------
+        // 8x32  |
+        let tiles = [
+            Tile::new("a", 0, 0),
+            Tile::new("b", 1, 0),
+            Tile::new("c", 0, 1).rotate(Rotation::Deg90),
+        ];
+        Layout::new(&tiles, &[(200, 8), (200, 8), (8, 32)]).unwrap()
+    }
+
+    #[test]
+    fn size_exceeds_u8() {
+        let layout = layout();
+        assert_eq!(layout.width(), 400);
+        assert_eq!(layout.height(), 40);
+        assert_eq!(
+            layout.areas(),
+            [
+                Area {
+                    x: 0,
+                    y: 0,
+                    width: 200,
+                    height: 8
+                },
+                Area {
+                    x: 200,
+                    y: 0,
+                    width: 200,
+                    height: 8
+                },
+                Area {
+                    x: 0,
+                    y: 8,
+                    width: 8,
+                    height: 32
+                },
+            ]
+        );
+        assert!(layout.contains(0, 0, 400, 40));
+        assert!(!layout.contains(399, 0, 2, 1));
+    }
+
+    #[test]
+    fn duplicate_position_is_rejected() {
+        let tiles = [Tile::new("a", 1, 0), Tile::new("b", 1, 0)];
+        assert!(matches!(
+            Layout::new(&tiles, &[(8, 8), (8, 8)]),
+            Err(Error::DuplicateTile { column: 1, row: 0 })
+        ));
+    }
+
+    #[test]
+    fn largest_grid_fits() {
+        let tiles = (0..=u8::MAX)
+            .map(|column| Tile::new("a", column, column))
+            .collect::<Vec<_>>();
+        let layout = Layout::new(&tiles, &vec![(255, 255); tiles.len()]).unwrap();
+        assert_eq!(layout.width(), 256 * 255);
+        assert_eq!(layout.height(), 256 * 255);
+    }
+
+    #[test]
+    fn split_region() {
+        let layout = layout();
+        let intersections = layout.intersections(190, 4, 20, 10).collect::<Vec<_>>();
+        assert_eq!(
+            intersections,
+            [
+                Intersection {
+                    tile: 0,
+                    x: 190,
+                    y: 4,
+                    width: 10,
+                    height: 4,
+                    region_x: 0,
+                    region_y: 0,
+                },
+                Intersection {
+                    tile: 1,
+                    x: 0,
+                    y: 4,
+                    width: 10,
+                    height: 4,
+                    region_x: 10,
+                    region_y: 0,
+                },
+            ]
+        );
+        // The gap right of tile c is skipped
+        let intersections = layout.intersections(6, 10, 4, 1).collect::<Vec<_>>();
+        assert_eq!(
+            intersections,
+            [Intersection {
+                tile: 2,
+                x: 6,
+                y: 2,
+                width: 2,
+                height: 1,
+                region_x: 0,
+                region_y: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn intersection_colors() {
+        let colors = (0..6)
+            .map(|value| Rgb::new(value, 0, 0))
+            .collect::<Vec<_>>();
+        let intersection = Intersection {
+            tile: 0,
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            region_x: 1,
+            region_y: 1,
+        };
+        assert_eq!(
+            intersection.colors(&colors, 3),
+            [Rgb::new(4, 0, 0), Rgb::new(5, 0, 0)]
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Map coordinates of a rotated, mirrored or shifted view onto the physical display.
+//!
+//! The clients wrap this as `TransformedClient`.
+
+use crate::protocol::{Message, contiguous_colors_length};
+
+/// Clockwise rotation of the drawn content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// Rotation, mirroring and origin offset applied to every command.
+///
+/// Mirroring happens in the rotated view, the offset moves the origin within the rotated and mirrored view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Transform {
+    rotation: Rotation,
+    mirror_horizontal: bool,
+    mirror_vertical: bool,
+    offset_x: u8,
+    offset_y: u8,
+}
+
+impl Transform {
+    pub const fn new() -> Self {
+        Self {
+            rotation: Rotation::Deg0,
+            mirror_horizontal: false,
+            mirror_vertical: false,
+            offset_x: 0,
+            offset_y: 0,
+        }
+    }
+
+    pub const fn rotate(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Flip left and right.
+    pub const fn mirror_horizontal(mut self, mirror: bool) -> Self {
+        self.mirror_horizontal = mirror;
+        self
+    }
+
+    /// Flip top and bottom.
+    pub const fn mirror_vertical(mut self, mirror: bool) -> Self {
+        self.mirror_vertical = mirror;
+        self
+    }
+
+    /// Move the origin to the given position. The area left and above of it is not drawable anymore.
+    pub const fn offset(mut self, x: u8, y: u8) -> Self {
+        self.offset_x = x;
+        self.offset_y = y;
+        self
+    }
+
+    /// Size of the rotated view before the offset is applied.
+    const fn view_size(self, physical_width: u8, physical_height: u8) -> (u8, u8) {
+        match self.rotation {
+            Rotation::Deg0 | Rotation::Deg180 => (physical_width, physical_height),
+            Rotation::Deg90 | Rotation::Deg270 => (physical_height, physical_width),
+        }
+    }
+
+    /// Width and height as seen through the transform.
+    #[must_use]
+    pub const fn size(self, physical_width: u8, physical_height: u8) -> (u8, u8) {
+        let (width, height) = self.view_size(physical_width, physical_height);
+        (
+            width.saturating_sub(self.offset_x),
+            height.saturating_sub(self.offset_y),
+        )
+    }
+
+    /// Physical position of the given point.
+    ///
+    /// The point has to be within [`size`](Self::size).
+    #[must_use]
+    pub const fn point(self, x: u8, y: u8, physical_width: u8, physical_height: u8) -> (u8, u8) {
+        let (view_width, view_height) = self.view_size(physical_width, physical_height);
+        let mut x = x + self.offset_x;
+        let mut y = y + self.offset_y;
+        if self.mirror_horizontal {
+            x = view_width - 1 - x;
+        }
+        if self.mirror_vertical {
+            y = view_height - 1 - y;
+        }
+        match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (physical_width - 1 - y, x),
+            Rotation::Deg180 => (physical_width - 1 - x, physical_height - 1 - y),
+            Rotation::Deg270 => (y, physical_height - 1 - x),
+        }
+    }
+
+    /// Physical top left corner, width and height of the given area.
+    ///
+    /// The area has to be within [`size`](Self::size) and must not be empty.
+    const fn area(
+        self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        physical_width: u8,
+        physical_height: u8,
+    ) -> (u8, u8, u8, u8) {
+        let (first_x, first_y) = self.point(x, y, physical_width, physical_height);
+        let (last_x, last_y) = self.point(
+            x + (width - 1),
+            y + (height - 1),
+            physical_width,
+            physical_height,
+        );
+        let (x, width) = if first_x <= last_x {
+            (first_x, last_x - first_x + 1)
+        } else {
+            (last_x, first_x - last_x + 1)
+        };
+        let (y, height) = if first_y <= last_y {
+            (first_y, last_y - first_y + 1)
+        } else {
+            (last_y, first_y - last_y + 1)
+        };
+        (x, y, width, height)
+    }
+
+    /// The message to send to the physical display.
+    ///
+    /// The message has to fit within [`size`](Self::size), see [`Message::validate`].
+    /// Returns `None` when the message does not draw anything.
+    #[must_use]
+    pub fn message(
+        self,
+        message: &Message,
+        physical_width: u8,
+        physical_height: u8,
+    ) -> Option<Message> {
+        let message = match *message {
+            Message::Fill { red, green, blue } => {
+                if self.offset_x == 0 && self.offset_y == 0 {
+                    return Some(message.clone());
+                }
+                let (width, height) = self.size(physical_width, physical_height);
+                return self.message(
+                    &Message::Rectangle {
+                        x: 0,
+                        y: 0,
+                        width,
+                        height,
+                        red,
+                        green,
+                        blue,
+                    },
+                    physical_width,
+                    physical_height,
+                );
+            }
+            Message::Pixel {
+                x,
+                y,
+                red,
+                green,
+                blue,
+            } => {
+                let (x, y) = self.point(x, y, physical_width, physical_height);
+                Message::Pixel {
+                    x,
+                    y,
+                    red,
+                    green,
+                    blue,
+                }
+            }
+            Message::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => {
+                if width == 0 || height == 0 {
+                    return None;
+                }
+                let (x, y, width, height) =
+                    self.area(x, y, width, height, physical_width, physical_height);
+                Message::Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    red,
+                    green,
+                    blue,
+                }
+            }
+            Message::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                ref colors,
+            } => {
+                if width == 0 || height == 0 {
+                    return None;
+                }
+                let (target_x, target_y, target_width, target_height) =
+                    self.area(x, y, width, height, physical_width, physical_height);
+                let mut target = vec![0; contiguous_colors_length(target_width, target_height)];
+                let mut source = colors.chunks_exact(3);
+                for y in y..y + height {
+                    for x in x..x + width {
+                        let (physical_x, physical_y) =
+                            self.point(x, y, physical_width, physical_height);
+                        let index = usize::from(physical_y - target_y) * usize::from(target_width)
+                            + usize::from(physical_x - target_x);
+                        let color = source.next()?;
+                        target[index * 3..index * 3 + 3].copy_from_slice(color);
+                    }
+                }
+                Message::Contiguous {
+                    x: target_x,
+                    y: target_y,
+                    width: target_width,
+                    height: target_height,
+                    colors: target,
+                }
+            }
+        };
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Physical display of 3x2 with each pixel numbered.
+    /// Draw every logical pixel with its number and return the physical result.
+    fn render(transform: Transform) -> Vec<u8> {
+        let (width, height) = transform.size(3, 2);
+        let colors = (0..contiguous_colors_length(width, height) / 3)
+            .flat_map(|index| [u8::try_from(index).unwrap(); 3])
+            .collect();
+        let message = Message::Contiguous {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            colors,
+        };
+        let mut physical = vec![u8::MAX; 6];
+        if let Some(Message::Contiguous {
+            x,
+            y,
+            width,
+            colors,
+            ..
+        }) = transform.message(&message, 3, 2)
+        {
+            for (index, color) in colors.chunks_exact(3).enumerate() {
+                let index = u8::try_from(index).unwrap();
+                let physical_x = x + index % width;
+                let physical_y = y + index / width;
+                physical[usize::from(physical_y) * 3 + usize::from(physical_x)] = color[0];
+            }
+        }
+        physical
+    }
+
+    #[test]
+    fn identity() {
+        assert_eq!(render(Transform::new()), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn rotations() {
+        // Logical 2x3
+        // 0 1
+        // 2 3
+        // 4 5
+        assert_eq!(Transform::new().rotate(Rotation::Deg90).size(3, 2), (2, 3));
+        assert_eq!(
+            render(Transform::new().rotate(Rotation::Deg90)),
+            [4, 2, 0, 5, 3, 1]
+        );
+        assert_eq!(
+            render(Transform::new().rotate(Rotation::Deg180)),
+            [5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            render(Transform::new().rotate(Rotation::Deg270)),
+            [1, 3, 5, 0, 2, 4]
+        );
+    }
+
+    #[test]
+    fn mirrors() {
+        assert_eq!(
+            render(Transform::new().mirror_horizontal(true)),
+            [2, 1, 0, 5, 4, 3]
+        );
+        assert_eq!(
+            render(Transform::new().mirror_vertical(true)),
+            [3, 4, 5, 0, 1, 2]
+        );
+        assert_eq!(
+            render(
+                Transform::new()
+                    .rotate(Rotation::Deg90)
+                    .mirror_horizontal(true)
+            ),
+            [5, 3, 1, 4, 2, 0]
+        );
+    }
+
+    #[test]
+    fn offset() {
+        let transform = Transform::new().offset(1, 1);
+        assert_eq!(transform.size(3, 2), (2, 1));
+        assert_eq!(
+            render(transform),
+            [u8::MAX, u8::MAX, u8::MAX, u8::MAX, 0, 1]
+        );
+        assert_eq!(
+            transform.message(
+                &Message::Fill {
+                    red: 1,
+                    green: 2,
+                    blue: 3
+                },
+                3,
+                2
+            ),
+            Some(Message::Rectangle {
+                x: 1,
+                y: 1,
+                width: 2,
+                height: 1,
+                red: 1,
+                green: 2,
+                blue: 3
+            })
+        );
+    }
+
+    #[test]
+    fn rectangle() {
+        let transform = Transform::new().rotate(Rotation::Deg90);
+        let rectangle = Message::Rectangle {
+            x: 0,
+            y: 1,
+            width: 1,
+            height: 2,
+            red: 1,
+            green: 1,
+            blue: 1,
+        };
+        assert_eq!(
+            transform.message(&rectangle, 3, 2),
+            Some(Message::Rectangle {
+                x: 0,
+                y: 0,
+                width: 2,
+                height: 1,
+                red: 1,
+                green: 1,
+                blue: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! Small reusable components like a clock or a progress bar, each drawn into a region of the display.
+//!
+//! A [`Region`] remembers what it showed last, so only the changed pixels are sent on every tick.
+//! The clients send it with `update_widget`.
+
+use core::time::Duration;
+
+use crate::color::Rgb;
+use crate::font::{TextLayout, TextStyle};
+use crate::framebuffer::Framebuffer;
+use crate::protocol::Message;
+
+/// Something drawn into a [`Region`].
+pub trait Widget {
+    /// Draw the whole widget. The framebuffer has the size of the region.
+    fn render(&self, framebuffer: &mut Framebuffer);
+}
+
+/// A [`Widget`] placed on an area of the display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<W> {
+    x: u8,
+    y: u8,
+    buffer: Framebuffer,
+    widget: W,
+}
+
+impl<W: Widget> Region<W> {
+    /// The display content is unknown, so the first update sends the whole region.
+    #[must_use]
+    pub fn new(x: u8, y: u8, width: u8, height: u8, widget: W) -> Self {
+        Self {
+            x,
+            y,
+            buffer: Framebuffer::new(width, height),
+            widget,
+        }
+    }
+
+    #[must_use]
+    pub const fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    /// Change the widget. The change is sent on the next update.
+    pub const fn widget_mut(&mut self) -> &mut W {
+        &mut self.widget
+    }
+
+    /// Render the widget and return the messages needed to update the region on the display.
+    ///
+    /// Call [`mark_presented`](Self::mark_presented) once they are sent.
+    #[must_use]
+    pub fn changes(&mut self) -> Vec<Message> {
+        self.widget.render(&mut self.buffer);
+        self.buffer
+            .diff()
+            .into_iter()
+            .map(|message| self.translate(message))
+            .collect()
+    }
+
+    /// Remember the rendered content as shown on the display.
+    pub fn mark_presented(&mut self) {
+        self.buffer.mark_presented();
+    }
+
+    /// Send the whole region on the next update, for example when something else drew over it.
+    pub fn invalidate(&mut self) {
+        self.buffer.invalidate();
+    }
+
+    /// Move the message from the region onto the display.
+    fn translate(&self, message: Message) -> Message {
+        match message {
+            Message::Fill { red, green, blue } => Message::Rectangle {
+                x: self.x,
+                y: self.y,
+                width: self.buffer.width(),
+                height: self.buffer.height(),
+                red,
+                green,
+                blue,
+            },
+            Message::Pixel {
+                x,
+                y,
+                red,
+                green,
+                blue,
+            } => Message::Pixel {
+                x: x.saturating_add(self.x),
+                y: y.saturating_add(self.y),
+                red,
+                green,
+                blue,
+            },
+            Message::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                red,
+                green,
+                blue,
+            } => Message::Rectangle {
+                x: x.saturating_add(self.x),
+                y: y.saturating_add(self.y),
+                width,
+                height,
+                red,
+                green,
+                blue,
+            },
+            Message::Contiguous {
+                x,
+                y,
+                width,
+                height,
+                colors,
+            } => Message::Contiguous {
+                x: x.saturating_add(self.x),
+                y: y.saturating_add(self.y),
+                width,
+                height,
+                colors,
+            },
+        }
+    }
+}
+
+/// Draw the text in the middle of the framebuffer.
+fn draw_centered(framebuffer: &mut Framebuffer, layout: &TextLayout) {
+    let left = (i64::from(framebuffer.width()) - i64::from(layout.width())) / 2;
+    let top = (i64::from(framebuffer.height()) - i64::from(layout.height())) / 2;
+    for (x, y, length, color) in layout.runs() {
+        let Ok(y) = u8::try_from(top + i64::from(y)) else {
+            continue;
+        };
+        for x in x..x + length {
+            if let Ok(x) = u8::try_from(left + i64::from(x)) {
+                framebuffer.set_pixel(x, y, color.to_array());
+            }
+        }
+    }
+}
+
+/// Time of day as hours and minutes with optional seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct Clock {
+    style: TextStyle,
+    background: Rgb,
+    seconds: bool,
+    time: (u8, u8, u8),
+}
+
+impl Clock {
+    /// Shows 00:00 without seconds on black.
+    pub const fn new(style: TextStyle) -> Self {
+        Self {
+            style,
+            background: Rgb::BLACK,
+            seconds: false,
+            time: (0, 0, 0),
+        }
+    }
+
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub const fn show_seconds(mut self, seconds: bool) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    pub const fn set_time(&mut self, hours: u8, minutes: u8, seconds: u8) {
+        self.time = (hours, minutes, seconds);
+    }
+
+    /// The shown text like `09:41`.
+    #[must_use]
+    pub fn text(&self) -> String {
+        let (hours, minutes, seconds) = self.time;
+        if self.seconds {
+            format!("{hours:02}:{minutes:02}:{seconds:02}")
+        } else {
+            format!("{hours:02}:{minutes:02}")
+        }
+    }
+}
+
+impl Widget for Clock {
+    fn render(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.fill(self.background.to_array());
+        draw_centered(framebuffer, &self.style.layout(&self.text()));
+    }
+}
+
+/// Time left as minutes and seconds, with hours when there is more than an hour left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct Countdown {
+    style: TextStyle,
+    background: Rgb,
+    remaining: Duration,
+}
+
+impl Countdown {
+    pub const fn new(style: TextStyle, duration: Duration) -> Self {
+        Self {
+            style,
+            background: Rgb::BLACK,
+            remaining: duration,
+        }
+    }
+
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    #[must_use]
+    pub const fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    pub const fn set_remaining(&mut self, remaining: Duration) {
+        self.remaining = remaining;
+    }
+
+    /// Count down by the time passed, for example [`Frame::delta`](crate::pacing::Frame::delta).
+    pub const fn advance(&mut self, delta: Duration) {
+        self.remaining = self.remaining.saturating_sub(delta);
+    }
+
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// The shown text like `4:59` or `1:04:59`.
+    ///
+    /// Started seconds count, so `0:00` is only shown once finished.
+    #[must_use]
+    pub fn text(&self) -> String {
+        let seconds = self.remaining.as_secs() + u64::from(self.remaining.subsec_nanos() > 0);
+        if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        }
+    }
+}
+
+impl Widget for Countdown {
+    fn render(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.fill(self.background.to_array());
+        draw_centered(framebuffer, &self.style.layout(&self.text()));
+    }
+}
+
+/// Bar filling the region from the left, or from the bottom when vertical.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[must_use]
+pub struct ProgressBar {
+    color: Rgb,
+    background: Rgb,
+    vertical: bool,
+    progress: f32,
+}
+
+impl ProgressBar {
+    /// Empty horizontal bar on black.
+    pub const fn new(color: Rgb) -> Self {
+        Self {
+            color,
+            background: Rgb::BLACK,
+            vertical: false,
+            progress: 0.0,
+        }
+    }
+
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub const fn vertical(mut self, vertical: bool) -> Self {
+        self.vertical = vertical;
+        self
+    }
+
+    #[must_use]
+    pub const fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Between 0.0 for empty and 1.0 for full.
+    pub const fn set_progress(&mut self, progress: f32) {
+        self.progress = progress.clamp(0.0, 1.0);
+    }
+}
+
+impl Widget for ProgressBar {
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn render(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.fill(self.background.to_array());
+        let (width, height) = (framebuffer.width(), framebuffer.height());
+        let length = if self.vertical { height } else { width };
+        let filled = (f32::from(length) * self.progress).round() as u8;
+        if self.vertical {
+            framebuffer.rectangle(0, height - filled, width, filled, self.color.to_array());
+        } else {
+            framebuffer.rectangle(0, 0, filled, height, self.color.to_array());
+        }
+    }
+}
+
+/// Number with an optional unit, colored depending on its value.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Gauge {
+    style: TextStyle,
+    background: Rgb,
+    decimals: u8,
+    unit: String,
+    /// Ascending lower limits of the value with their color
+    thresholds: Vec<(f32, Rgb)>,
+    value: f32,
+}
+
+impl Gauge {
+    /// Shows 0 without decimals and unit on black.
+    pub const fn new(style: TextStyle) -> Self {
+        Self {
+            style,
+            background: Rgb::BLACK,
+            decimals: 0,
+            unit: String::new(),
+            thresholds: Vec::new(),
+            value: 0.0,
+        }
+    }
+
+    pub const fn background(mut self, background: Rgb) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Digits shown after the decimal point.
+    pub const fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Shown right after the value, for example `°C` or `%`.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = unit.into();
+        self
+    }
+
+    /// Show the value in the given color once it reaches the limit.
+    /// Below every limit the color of the [`TextStyle`] is used.
+    pub fn threshold(mut self, limit: f32, color: Rgb) -> Self {
+        let index = self
+            .thresholds
+            .partition_point(|(other, _)| *other <= limit);
+        self.thresholds.insert(index, (limit, color));
+        self
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub const fn set_value(&mut self, value: f32) {
+        self.value = value;
+    }
+
+    /// The shown text like `21.5°C`.
+    #[must_use]
+    pub fn text(&self) -> String {
+        format!("{:.*}{}", usize::from(self.decimals), self.value, self.unit)
+    }
+}
+
+impl Widget for Gauge {
+    fn render(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.fill(self.background.to_array());
+        let style = self
+            .thresholds
+            .iter()
+            .rev()
+            .find(|(limit, _)| self.value >= *limit)
+            .map_or(self.style, |(_, color)| self.style.color(*color));
+        draw_centered(framebuffer, &style.layout(&self.text()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::FONT_3X5;
+
+    const STYLE: TextStyle = TextStyle::new(&FONT_3X5, Rgb::WHITE);
+
+    /// Rows of the region with `#` for white and `.` for everything else
+    fn render(widget: &impl Widget, width: u8, height: u8) -> Vec<String> {
+        let mut framebuffer = Framebuffer::new(width, height);
+        widget.render(&mut framebuffer);
+        framebuffer
+            .pixels()
+            .chunks_exact(width.into())
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| if *pixel == [255; 3] { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn region_sends_only_changes() {
+        let mut region = Region::new(2, 1, 4, 2, ProgressBar::new(Rgb::WHITE));
+        assert_eq!(
+            region.changes(),
+            [Message::Rectangle {
+                x: 2,
+                y: 1,
+                width: 4,
+                height: 2,
+                red: 0,
+                green: 0,
+                blue: 0,
+            }]
+        );
+        region.mark_presented();
+        assert_eq!(region.changes(), []);
+
+        region.widget_mut().set_progress(0.5);
+        assert_eq!(
+            region.changes(),
+            [Message::Rectangle {
+                x: 2,
+                y: 1,
+                width: 2,
+                height: 2,
+                red: 255,
+                green: 255,
+                blue: 255,
+            }]
+        );
+        region.mark_presented();
+        region.invalidate();
+        assert!(!region.changes().is_empty());
+    }
+
+    #[test]
+    fn clock() {
+        let mut clock = Clock::new(STYLE);
+        clock.set_time(1, 2, 3);
+        assert_eq!(clock.text(), "01:02");
+        assert_eq!(clock.show_seconds(true).text(), "01:02:03");
+        // 19 pixels wide text centered in 21
+        let rows = render(&clock, 21, 6);
+        assert_eq!(rows[1], ".###..#......###.##..");
+        assert_eq!(rows[4], ".#.#..#...#..#.#.#...");
+    }
+
+    #[test]
+    fn countdown() {
+        let mut countdown = Countdown::new(STYLE, Duration::from_secs(3661));
+        assert_eq!(countdown.text(), "1:01:01");
+        countdown.advance(Duration::from_millis(3_660_500));
+        assert_eq!(countdown.text(), "0:01");
+        assert!(!countdown.is_finished());
+        countdown.advance(Duration::from_secs(5));
+        assert_eq!(countdown.text(), "0:00");
+        assert!(countdown.is_finished());
+        countdown.set_remaining(Duration::from_secs(299));
+        assert_eq!(countdown.text(), "4:59");
+        assert_eq!(render(&countdown, 15, 6)[5], "..#.....##..##.");
+    }
+
+    #[test]
+    fn progress_bar() {
+        let mut bar = ProgressBar::new(Rgb::WHITE);
+        bar.set_progress(0.5);
+        assert_eq!(render(&bar, 4, 1), ["##.."]);
+        bar.set_progress(2.0);
+        assert_eq!(render(&bar, 4, 1), ["####"]);
+        let mut bar = bar.vertical(true);
+        bar.set_progress(0.25);
+        assert_eq!(render(&bar, 1, 4), [".", ".", ".", "#"]);
+    }
+
+    #[test]
+    fn gauge() {
+        let mut gauge = Gauge::new(STYLE.color(Rgb::BLUE))
+            .decimals(1)
+            .unit("%")
+            .threshold(50.0, Rgb::WHITE)
+            .threshold(90.0, Rgb::RED);
+        gauge.set_value(42.0);
+        assert_eq!(gauge.text(), "42.0%");
+        assert!(render(&gauge, 19, 6).iter().all(|row| !row.contains('#')));
+        gauge.set_value(75.5);
+        assert_eq!(gauge.text(), "75.5%");
+        assert_eq!(render(&gauge, 19, 6)[5], ".#..##...#..##..#.#");
+        gauge.set_value(99.0);
+        assert!(render(&gauge, 19, 6).iter().all(|row| !row.contains('#')));
+    }
+}
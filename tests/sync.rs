@@ -0,0 +1,807 @@
+use core::ops::ControlFlow;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use esp_remotecontrolled_led_matrix_client::backoff::Backoff;
+use esp_remotecontrolled_led_matrix_client::compositor::{BlendMode, Compositor, Layer};
+use esp_remotecontrolled_led_matrix_client::correction::Correction;
+use esp_remotecontrolled_led_matrix_client::font::{FONT_3X5, TextStyle};
+use esp_remotecontrolled_led_matrix_client::framebuffer::Framebuffer;
+use esp_remotecontrolled_led_matrix_client::mock::MockServer;
+use esp_remotecontrolled_led_matrix_client::power::PowerBudget;
+use esp_remotecontrolled_led_matrix_client::protocol::{Bounds, Message};
+use esp_remotecontrolled_led_matrix_client::sync::{
+    Canvas as _, Client, DoubleBuffer, FrameLoop, ReconnectingClient, TransformedClient,
+};
+use esp_remotecontrolled_led_matrix_client::transform::{Rotation, Transform};
+use esp_remotecontrolled_led_matrix_client::widgets::{ProgressBar, Region};
+use esp_remotecontrolled_led_matrix_client::{Error, Rgb};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const BACKOFF: Backoff = Backoff {
+    initial: Duration::from_millis(10),
+    max: Duration::from_millis(50),
+    factor: 2,
+    max_attempts: Some(10),
+};
+
+fn setup() -> (MockServer, Client) {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).unwrap();
+    (server, client)
+}
+
+/// Enough full frames to fill the buffers of a stalled connection in a few writes
+fn many_frames() -> Vec<Message> {
+    let frame = Message::Contiguous {
+        x: 0,
+        y: 0,
+        width: 8,
+        height: 4,
+        colors: vec![42; 8 * 4 * 3],
+    };
+    vec![frame; 10_000]
+}
+
+#[test]
+fn handshake() {
+    let (_server, client) = setup();
+    assert_eq!(client.width(), 8);
+    assert_eq!(client.height(), 4);
+    assert_eq!(client.total_pixels(), 32);
+}
+
+#[test]
+fn fill() {
+    let (server, client) = setup();
+    client.fill(Rgb::new(1, 2, 3)).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    assert!(server.framebuffer().iter().all(|color| color == &[1, 2, 3]));
+}
+
+#[test]
+fn pixel() {
+    let (server, client) = setup();
+    client.pixel(7, 3, Rgb::new(255, 0, 0)).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    assert_eq!(server.pixel(7, 3), Some([255, 0, 0]));
+    assert_eq!(server.pixel(6, 3), Some([0, 0, 0]));
+    assert_eq!(
+        server.messages(),
+        [Message::Pixel {
+            x: 7,
+            y: 3,
+            red: 255,
+            green: 0,
+            blue: 0,
+        }]
+    );
+}
+
+#[test]
+fn rectangle() {
+    let (server, client) = setup();
+    client.rectangle(3, 1, 3, 2, Rgb::new(0, 0, 255)).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    for y in 0..4 {
+        for x in 0..8 {
+            let expected = if (3..6).contains(&x) && (1..3).contains(&y) {
+                [0, 0, 255]
+            } else {
+                [0, 0, 0]
+            };
+            assert_eq!(server.pixel(x, y), Some(expected), "at {x} {y}");
+        }
+    }
+}
+
+#[test]
+fn contiguous() {
+    let (server, client) = setup();
+    client
+        .contiguous(
+            6,
+            2,
+            2,
+            2,
+            &[
+                Rgb::new(1, 1, 1),
+                Rgb::new(2, 2, 2),
+                Rgb::new(3, 3, 3),
+                Rgb::new(4, 4, 4),
+            ],
+        )
+        .unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    assert_eq!(server.pixel(6, 2), Some([1, 1, 1]));
+    assert_eq!(server.pixel(7, 2), Some([2, 2, 2]));
+    assert_eq!(server.pixel(6, 3), Some([3, 3, 3]));
+    assert_eq!(server.pixel(7, 3), Some([4, 4, 4]));
+}
+
+#[test]
+fn contiguous_rejects_invalid_input() {
+    let (_server, client) = setup();
+    assert!(matches!(
+        client.contiguous(7, 0, 2, 1, &[Rgb::BLACK; 2]),
+        Err(Error::OutOfBounds { x: 7, .. })
+    ));
+    assert!(matches!(
+        client.send(&Message::Contiguous {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            colors: vec![0; 5],
+        }),
+        Err(Error::ColorBufferLength {
+            expected: 6,
+            actual: 5
+        })
+    ));
+}
+
+#[test]
+fn pixel_and_rectangle_reject_out_of_bounds() {
+    let (server, client) = setup();
+    assert!(matches!(
+        client.pixel(8, 0, Rgb::new(1, 1, 1)),
+        Err(Error::OutOfBounds { x: 8, y: 0, .. })
+    ));
+    assert!(matches!(
+        client.rectangle(2, 2, 3, 3, Rgb::new(1, 1, 1)),
+        Err(Error::OutOfBounds { height: 3, .. })
+    ));
+    client.flush().unwrap();
+    assert!(!server.wait_for_messages(1, Duration::from_millis(100)));
+}
+
+#[test]
+fn clip_out_of_bounds() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .bounds(Bounds::Clip)
+        .connect(server.address())
+        .unwrap();
+    client.pixel(8, 0, Rgb::new(1, 1, 1)).unwrap();
+    client.rectangle(6, 2, 200, 200, Rgb::new(2, 2, 2)).unwrap();
+    client
+        .contiguous(
+            7,
+            0,
+            2,
+            2,
+            &[
+                Rgb::new(3, 3, 3),
+                Rgb::new(4, 4, 4),
+                Rgb::new(5, 5, 5),
+                Rgb::new(6, 6, 6),
+            ],
+        )
+        .unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(2, TIMEOUT));
+    assert_eq!(
+        server.take_messages(),
+        [
+            Message::Rectangle {
+                x: 6,
+                y: 2,
+                width: 2,
+                height: 2,
+                red: 2,
+                green: 2,
+                blue: 2,
+            },
+            Message::Contiguous {
+                x: 7,
+                y: 0,
+                width: 1,
+                height: 2,
+                colors: vec![3, 3, 3, 5, 5, 5],
+            },
+        ]
+    );
+}
+
+#[test]
+fn double_buffer_sends_on_present() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).unwrap();
+    let mut buffer = DoubleBuffer::new(client.clone());
+    buffer.fill(Rgb::new(1, 1, 1));
+    buffer.pixel(2, 1, Rgb::RED);
+    assert_eq!(client.bytes_sent(), 0);
+    let received = buffer.back().diff().len();
+    buffer.present().unwrap();
+    assert!(server.wait_for_messages(received, TIMEOUT));
+    assert_eq!(server.pixel(0, 0), Some([1, 1, 1]));
+    assert_eq!(server.pixel(2, 1), Some([255, 0, 0]));
+    drop(server.take_messages());
+
+    buffer.rectangle(0, 0, 2, 1, Rgb::GREEN);
+    buffer.present().unwrap();
+    assert!(server.wait_for_messages(received + 1, TIMEOUT));
+    assert_eq!(
+        server.take_messages(),
+        [Message::Rectangle {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+            red: 0,
+            green: 255,
+            blue: 0,
+        }]
+    );
+}
+
+#[test]
+fn double_buffer_contiguous_checks_length() {
+    let (_server, client) = setup();
+    let mut buffer = DoubleBuffer::new(client);
+    buffer
+        .contiguous(7, 0, 2, 1, &[Rgb::RED, Rgb::GREEN])
+        .unwrap();
+    assert_eq!(buffer.back().pixel(7, 0), Some([255, 0, 0]));
+    assert!(matches!(
+        buffer.contiguous(0, 0, 2, 2, &[Rgb::RED; 3]),
+        Err(Error::ColorBufferLength {
+            expected: 12,
+            actual: 9
+        })
+    ));
+    assert_eq!(buffer.back().pixel(0, 0), Some([0, 0, 0]));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn display_image() {
+    use esp_remotecontrolled_led_matrix_client::imaging::{ImageMode, ImageOptions};
+    use image::{DynamicImage, Rgb as ImageRgb, RgbImage};
+
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).unwrap();
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, ImageRgb([1, 2, 3])));
+    client
+        .display_image(&image, ImageOptions::new().mode(ImageMode::Stretch))
+        .unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    assert_eq!(server.framebuffer(), vec![[1, 2, 3]; 32]);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn play_animation() {
+    use esp_remotecontrolled_led_matrix_client::animation::{Animation, LoopCount};
+    use esp_remotecontrolled_led_matrix_client::imaging::ImageOptions;
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, Rgba, RgbaImage};
+
+    let mut gif = Vec::new();
+    GifEncoder::new(&mut gif)
+        .encode_frames([[1, 2, 3, 255], [4, 5, 6, 255]].map(|color| {
+            Frame::from_parts(
+                RgbaImage::from_pixel(8, 4, Rgba(color)),
+                0,
+                0,
+                Delay::from_numer_denom_ms(20, 1),
+            )
+        }))
+        .unwrap();
+    let animation = Animation::from_bytes(&gif, 8, 4, ImageOptions::new())
+        .unwrap()
+        .with_loop_count(LoopCount::Finite(2));
+
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).unwrap();
+    client.play_animation(&animation).unwrap();
+    // Frames might be skipped on a slow machine but the last frame is always shown
+    let start = std::time::Instant::now();
+    while server.framebuffer() != vec![[4, 5, 6]; 32] {
+        assert!(start.elapsed() < TIMEOUT, "last frame not shown");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let wrong_size = Animation::from_bytes(&gif, 4, 4, ImageOptions::new()).unwrap();
+    assert!(matches!(
+        client.play_animation(&wrong_size),
+        Err(Error::SizeMismatch { .. })
+    ));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn load_animation_dithers_with_correction() {
+    use esp_remotecontrolled_led_matrix_client::imaging::{Dither, ImageOptions};
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, Frame, Rgba, RgbaImage};
+
+    let mut gif = Vec::new();
+    GifEncoder::new(&mut gif)
+        .encode_frames([Frame::from_parts(
+            RgbaImage::from_pixel(8, 4, Rgba([255, 0, 0, 255])),
+            0,
+            0,
+            Delay::from_numer_denom_ms(20, 1),
+        )])
+        .unwrap();
+
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .correction(Correction::new().brightness(0.1))
+        .connect(server.address())
+        .unwrap();
+    let plain = client.load_animation(&gif, ImageOptions::new()).unwrap();
+    let dithered = client
+        .load_animation(&gif, ImageOptions::new().dither(Dither::Bayer))
+        .unwrap();
+    assert_eq!((dithered.width(), dithered.height()), (8, 4));
+    assert_ne!(plain.frames()[0].pixels, dithered.frames()[0].pixels);
+}
+
+#[cfg(feature = "embedded-graphics")]
+#[test]
+fn run_ticker() {
+    use embedded_graphics::mono_font::ascii::FONT_5X7;
+    use esp_remotecontrolled_led_matrix_client::ticker::Ticker;
+
+    let (server, client) = setup();
+    let mut ticker = Ticker::new(&FONT_5X7)
+        .segment("I", Rgb::RED)
+        .speed(400.0)
+        .looping(false);
+    client
+        .run_ticker(&mut ticker, 100.0, |_ticker, _frame| {
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert!(ticker.is_finished(8));
+    assert!(server.wait_for_messages(1, TIMEOUT));
+
+    // Stopped right away without scrolling
+    ticker.restart();
+    let mut frames = 0;
+    client
+        .run_ticker(&mut ticker, 100.0, |ticker, frame| {
+            ticker.pause();
+            frames += 1;
+            if frame.number < 2 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        })
+        .unwrap();
+    assert_eq!(frames, 3);
+    assert!(!ticker.is_finished(8));
+}
+
+#[test]
+fn frame_loop_paces_frames() {
+    let server = MockServer::start(8, 4).unwrap();
+    let mut client = Client::connect(server.address()).unwrap();
+    let mut frame_loop = FrameLoop::new(100.0);
+    frame_loop
+        .run(&mut client, |client, frame| {
+            client.rectangle(0, 0, 2, 2, Rgb::new(1, 2, 3))?;
+            Ok(if frame.number < 4 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            })
+        })
+        .unwrap();
+    let stats = frame_loop.stats();
+    assert_eq!(stats.frames(), 5);
+    assert_eq!(stats.bytes_per_frame(), Some(8.0));
+    assert!(stats.fps().unwrap() < 150.0);
+    assert!(server.wait_for_messages(5, TIMEOUT));
+}
+
+#[test]
+fn canvas_clips_off_screen() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).unwrap();
+    client.draw_pixel(-1, 0, Rgb::new(1, 1, 1)).unwrap();
+    client.draw_pixel(3, 2, Rgb::new(2, 2, 2)).unwrap();
+    client
+        .draw_rectangle(-300, 3, 1000, 5, Rgb::new(3, 3, 3))
+        .unwrap();
+    client
+        .draw_contiguous(
+            -1,
+            -1,
+            2,
+            2,
+            &[
+                Rgb::new(4, 4, 4),
+                Rgb::new(5, 5, 5),
+                Rgb::new(6, 6, 6),
+                Rgb::new(7, 7, 7),
+            ],
+        )
+        .unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(3, TIMEOUT));
+    assert_eq!(
+        server.take_messages(),
+        [
+            Message::Pixel {
+                x: 3,
+                y: 2,
+                red: 2,
+                green: 2,
+                blue: 2,
+            },
+            Message::Rectangle {
+                x: 0,
+                y: 3,
+                width: 8,
+                height: 1,
+                red: 3,
+                green: 3,
+                blue: 3,
+            },
+            Message::Contiguous {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+                colors: vec![7, 7, 7],
+            },
+        ]
+    );
+}
+
+#[test]
+fn draw_text() {
+    let (server, client) = setup();
+    let style = TextStyle::new(&FONT_3X5, Rgb::new(1, 2, 3));
+    client.draw_text(-1, -2, "-1", &style).unwrap();
+    client.flush().unwrap();
+    // The minus is in row 3 and the 1 covers rows 1 to 5 of the line. Row 1 is off-screen.
+    assert!(server.wait_for_messages(5, TIMEOUT));
+    assert_eq!(server.pixel(0, 1), Some([1, 2, 3]));
+    assert_eq!(server.pixel(2, 1), Some([0, 0, 0]));
+    assert_eq!(server.pixel(4, 0), Some([1, 2, 3]));
+
+    let style = style.background(Rgb::new(4, 4, 4));
+    client.draw_text(5, 0, "1", &style).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(6, TIMEOUT));
+    assert_eq!(server.pixel(5, 0), Some([4, 4, 4]));
+    assert_eq!(server.pixel(6, 1), Some([1, 2, 3]));
+}
+
+#[test]
+fn update_widget() {
+    let (server, client) = setup();
+    let mut region = Region::new(2, 1, 4, 2, ProgressBar::new(Rgb::new(1, 2, 3)));
+    region.widget_mut().set_progress(0.5);
+    client.update_widget(&mut region).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(2, TIMEOUT));
+    assert_eq!(server.pixel(3, 2), Some([1, 2, 3]));
+    assert_eq!(server.pixel(4, 2), Some([0, 0, 0]));
+
+    // Unchanged widgets send nothing
+    client.update_widget(&mut region).unwrap();
+    region.widget_mut().set_progress(0.75);
+    client.update_widget(&mut region).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(3, TIMEOUT));
+    assert_eq!(server.messages().len(), 3);
+    assert_eq!(server.pixel(4, 2), Some([1, 2, 3]));
+    assert_eq!(server.pixel(6, 2), Some([0, 0, 0]));
+}
+
+#[test]
+fn display_composition() {
+    let (server, client) = setup();
+    let mut overlay = Layer::new(2, 1)
+        .with_offset(6, 3)
+        .with_blend_mode(BlendMode::Add);
+    overlay.fill([10, 20, 30, 255]);
+    let compositor = Compositor::new(8, 4)
+        .background(Rgb::new(1, 2, 3))
+        .layer(overlay);
+    client.display_composition(&compositor).unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    assert!(matches!(
+        server.messages().as_slice(),
+        [Message::Contiguous {
+            x: 0,
+            y: 0,
+            width: 8,
+            height: 4,
+            ..
+        }]
+    ));
+    assert_eq!(server.pixel(0, 0), Some([1, 2, 3]));
+    assert_eq!(server.pixel(7, 3), Some([11, 22, 33]));
+
+    let err = client
+        .display_composition(&Compositor::new(4, 4))
+        .unwrap_err();
+    assert!(matches!(err, Error::SizeMismatch { .. }));
+}
+
+#[test]
+fn power_budget_dims_frame_evenly() {
+    let server = MockServer::start(8, 4).unwrap();
+    let budget = PowerBudget::new(480.0).idle_milliamps_per_pixel(0.0);
+    let client = Client::builder()
+        .power_budget(budget)
+        .connect(server.address())
+        .unwrap();
+    let mut framebuffer = client.framebuffer();
+    let rows = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+    for (y, color) in (0..).zip(rows) {
+        framebuffer.rectangle(0, y, 8, 1, color);
+    }
+    client.present(&mut framebuffer).unwrap();
+    assert!(server.wait_for_messages(2, TIMEOUT));
+    // Twice the budget, so every row is at half brightness and not only the last ones
+    assert_eq!(server.pixel(7, 0), Some([127, 0, 0]));
+    assert_eq!(server.pixel(7, 1), Some([0, 127, 0]));
+    assert_eq!(server.pixel(7, 2), Some([0, 0, 127]));
+    assert_eq!(server.pixel(7, 3), Some([127, 127, 127]));
+    assert!(client.estimated_milliamps().unwrap() <= 480.0);
+}
+
+#[test]
+fn power_budget_ignores_rejected_messages() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .power_budget(PowerBudget::new(480.0))
+        .connect(server.address())
+        .unwrap();
+    let before = client.estimated_milliamps();
+    let messages = [
+        Message::Fill {
+            red: 255,
+            green: 255,
+            blue: 255,
+        },
+        Message::Pixel {
+            x: 8,
+            y: 0,
+            red: 1,
+            green: 1,
+            blue: 1,
+        },
+    ];
+    assert!(matches!(
+        client.send_all(&messages),
+        Err(Error::OutOfBounds { .. })
+    ));
+    assert_eq!(client.estimated_milliamps(), before);
+    assert_eq!(client.bytes_sent(), 0);
+}
+
+#[test]
+fn correction_applies_to_every_command() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .correction(Correction::new().brightness(0.5))
+        .connect(server.address())
+        .unwrap();
+    client.fill(Rgb::WHITE).unwrap();
+    client.pixel(0, 0, Rgb::new(100, 0, 0)).unwrap();
+    client.rectangle(1, 0, 1, 1, Rgb::new(0, 100, 0)).unwrap();
+    client
+        .contiguous(2, 0, 1, 1, &[Rgb::new(0, 0, 100)])
+        .unwrap();
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(4, TIMEOUT));
+    assert_eq!(server.pixel(0, 0), Some([50, 0, 0]));
+    assert_eq!(server.pixel(1, 0), Some([0, 50, 0]));
+    assert_eq!(server.pixel(2, 0), Some([0, 0, 50]));
+    assert_eq!(server.pixel(3, 0), Some([128, 128, 128]));
+}
+
+#[test]
+fn transformed_client() {
+    let (server, client) = setup();
+    let transform = Transform::new().rotate(Rotation::Deg90).offset(0, 2);
+    let client = TransformedClient::new(client, transform);
+    assert_eq!((client.width(), client.height()), (4, 6));
+    client.pixel(0, 0, Rgb::new(1, 1, 1)).unwrap();
+    client.rectangle(1, 5, 3, 1, Rgb::new(2, 2, 2)).unwrap();
+    client
+        .contiguous(0, 1, 2, 1, &[Rgb::new(3, 3, 3), Rgb::new(4, 4, 4)])
+        .unwrap();
+    assert!(matches!(
+        client.pixel(0, 6, Rgb::WHITE),
+        Err(Error::OutOfBounds { .. })
+    ));
+    client.flush().unwrap();
+    assert!(server.wait_for_messages(3, TIMEOUT));
+    assert_eq!(server.pixel(7, 0), Some([0, 0, 0]));
+    assert_eq!(server.pixel(5, 0), Some([1, 1, 1]));
+    assert_eq!(server.pixel(0, 1), Some([2, 2, 2]));
+    assert_eq!(server.pixel(0, 3), Some([2, 2, 2]));
+    assert_eq!(server.pixel(4, 0), Some([3, 3, 3]));
+    assert_eq!(server.pixel(4, 1), Some([4, 4, 4]));
+}
+
+#[test]
+fn present_framebuffer() {
+    let (server, client) = setup();
+    let mut framebuffer = client.framebuffer();
+    framebuffer.fill([0, 0, 64]);
+    framebuffer.rectangle(1, 1, 3, 2, [255, 255, 0]);
+    framebuffer.set_pixel(7, 3, [255, 0, 0]);
+    let mut sent = framebuffer.diff().len();
+    client.present(&mut framebuffer).unwrap();
+    assert!(framebuffer.diff().is_empty());
+
+    framebuffer.set_pixel(0, 0, [1, 2, 3]);
+    assert_eq!(framebuffer.diff().len(), 1);
+    sent += 1;
+    client.present(&mut framebuffer).unwrap();
+
+    assert!(server.wait_for_messages(sent, TIMEOUT));
+    assert_eq!(server.received(), sent);
+    assert_eq!(server.framebuffer(), framebuffer.pixels());
+}
+
+#[test]
+fn present_rejects_other_size() {
+    let (_server, client) = setup();
+    let mut framebuffer = Framebuffer::new(3, 3);
+    assert!(matches!(
+        client.present(&mut framebuffer),
+        Err(Error::SizeMismatch { .. })
+    ));
+}
+
+#[test]
+fn reconnecting_client_survives_disconnect() {
+    let server = MockServer::start(8, 4).unwrap();
+    let mut client = ReconnectingClient::connect(server.address().to_string(), BACKOFF)
+        .unwrap()
+        .replay(true);
+    let mut framebuffer = client.framebuffer();
+    framebuffer.fill([0, 255, 0]);
+    client.present(&mut framebuffer).unwrap();
+
+    server.disconnect_clients();
+    for _ in 0..100 {
+        if server.accepted() > 1 {
+            break;
+        }
+        client.pixel(0, 0, Rgb::new(0, 255, 0)).unwrap();
+        client.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.accepted(), 2);
+
+    framebuffer.set_pixel(1, 1, [255, 0, 0]);
+    client.present(&mut framebuffer).unwrap();
+    for _ in 0..100 {
+        if server.framebuffer() == framebuffer.pixels() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.framebuffer(), framebuffer.pixels());
+}
+
+#[test]
+fn reconnecting_client_present_skips_replay() {
+    let server = MockServer::start(8, 4).unwrap();
+    let mut client = ReconnectingClient::connect(server.address().to_string(), BACKOFF)
+        .unwrap()
+        .replay(true);
+    let mut framebuffer = client.framebuffer();
+    framebuffer.fill([0, 255, 0]);
+    client.present(&mut framebuffer).unwrap();
+    assert!(server.wait_for_messages(1, TIMEOUT));
+    drop(server.take_messages());
+
+    server.disconnect_clients();
+    for blue in 1..=100 {
+        if server.accepted() > 1 {
+            break;
+        }
+        framebuffer.fill([0, 0, blue]);
+        client.present(&mut framebuffer).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.accepted(), 2);
+    for _ in 0..100 {
+        if server.framebuffer() == framebuffer.pixels() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.framebuffer(), framebuffer.pixels());
+
+    let mut colors = Vec::new();
+    for mut message in server.take_messages() {
+        message.map_colors(|color| {
+            colors.push(color);
+            color
+        });
+    }
+    // Only the new frame without the one presented before the connection broke
+    let [red, green, blue] = framebuffer.pixels()[0];
+    assert!(!colors.is_empty());
+    assert!(
+        colors
+            .iter()
+            .all(|color| *color == Rgb::new(red, green, blue))
+    );
+}
+
+#[test]
+fn reconnecting_client_gives_up() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let backoff = Backoff {
+        max_attempts: Some(2),
+        ..BACKOFF
+    };
+    assert!(ReconnectingClient::connect(address.to_string(), backoff).is_err());
+}
+
+#[test]
+fn failed_write_closes_connection() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .write_timeout(Duration::from_millis(50))
+        .connect(server.address())
+        .unwrap();
+    server.stall(true);
+    let frames = many_frames();
+    let error = (0..100)
+        .find_map(|_| client.send_all(&frames).and_then(|()| client.flush()).err())
+        .expect("write never timed out");
+    assert!(error.is_timeout(), "{error:?}");
+    server.stall(false);
+
+    // The timed out write might have sent a partial command, nothing is allowed to follow it
+    let error = client.pixel(0, 0, Rgb::WHITE).unwrap_err();
+    assert!(error.is_connection_error(), "{error:?}");
+    assert!(client.flush().unwrap_err().is_connection_error());
+}
+
+#[test]
+fn handshake_timeout() {
+    // Accepts connections in the backlog but never sends the handshake
+    let silent = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let error = Client::builder()
+        .handshake_timeout(Duration::from_millis(50))
+        .connect(silent.local_addr().unwrap())
+        .err()
+        .unwrap();
+    assert!(error.is_timeout(), "{error:?}");
+}
+
+#[test]
+fn connection_refused() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let error = Client::builder()
+        .connect_timeout(TIMEOUT)
+        .connect(address)
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&error, Error::Io(error) if error.kind() == ErrorKind::ConnectionRefused),
+        "{error:?}"
+    );
+}
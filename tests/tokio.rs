@@ -0,0 +1,355 @@
+use core::ops::ControlFlow;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use esp_remotecontrolled_led_matrix_client::async_tokio::{
+    Client, FrameLoop, ReconnectingClient, TiledDisplay,
+};
+use esp_remotecontrolled_led_matrix_client::backoff::Backoff;
+use esp_remotecontrolled_led_matrix_client::mock::MockServer;
+use esp_remotecontrolled_led_matrix_client::power::PowerBudget;
+use esp_remotecontrolled_led_matrix_client::protocol::{Bounds, Message};
+use esp_remotecontrolled_led_matrix_client::tiling::Tile;
+use esp_remotecontrolled_led_matrix_client::transform::Rotation;
+use esp_remotecontrolled_led_matrix_client::{Error, Rgb};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn setup() -> (MockServer, Client) {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::connect(server.address()).await.unwrap();
+    (server, client)
+}
+
+/// Enough full frames to fill the buffers of a stalled connection in a few writes
+fn many_frames() -> Vec<Message> {
+    let frame = Message::Contiguous {
+        x: 0,
+        y: 0,
+        width: 8,
+        height: 4,
+        colors: vec![42; 8 * 4 * 3],
+    };
+    vec![frame; 10_000]
+}
+
+#[tokio::test]
+async fn handshake() {
+    let (_server, client) = setup().await;
+    assert_eq!(client.width(), 8);
+    assert_eq!(client.height(), 4);
+    assert_eq!(client.total_pixels(), 32);
+}
+
+#[tokio::test]
+async fn commands() {
+    let (server, client) = setup().await;
+    client.fill(Rgb::new(1, 2, 3)).await.unwrap();
+    client.pixel(0, 0, Rgb::new(255, 0, 0)).await.unwrap();
+    client
+        .rectangle(1, 1, 2, 2, Rgb::new(0, 255, 0))
+        .await
+        .unwrap();
+    client
+        .contiguous(6, 3, 2, 1, &[Rgb::new(4, 4, 4), Rgb::new(5, 5, 5)])
+        .await
+        .unwrap();
+    client.flush().await.unwrap();
+
+    let wait = tokio::task::spawn_blocking(move || {
+        assert!(server.wait_for_messages(4, TIMEOUT));
+        server
+    });
+    let server = wait.await.unwrap();
+
+    assert_eq!(server.pixel(0, 0), Some([255, 0, 0]));
+    assert_eq!(server.pixel(1, 0), Some([1, 2, 3]));
+    assert_eq!(server.pixel(2, 2), Some([0, 255, 0]));
+    assert_eq!(server.pixel(3, 2), Some([1, 2, 3]));
+    assert_eq!(server.pixel(6, 3), Some([4, 4, 4]));
+    assert_eq!(server.pixel(7, 3), Some([5, 5, 5]));
+}
+
+#[tokio::test]
+async fn contiguous_rejects_invalid_input() {
+    let (_server, client) = setup().await;
+    assert!(matches!(
+        client.contiguous(7, 0, 2, 1, &[Rgb::BLACK; 2]).await,
+        Err(Error::OutOfBounds { x: 7, .. })
+    ));
+    assert!(matches!(
+        client
+            .send(&Message::Contiguous {
+                x: 0,
+                y: 0,
+                width: 2,
+                height: 1,
+                colors: vec![0; 5],
+            })
+            .await,
+        Err(Error::ColorBufferLength {
+            expected: 6,
+            actual: 5
+        })
+    ));
+}
+
+#[tokio::test]
+async fn pixel_and_rectangle_reject_out_of_bounds() {
+    let (_server, client) = setup().await;
+    assert!(matches!(
+        client.pixel(0, 4, Rgb::new(1, 1, 1)).await,
+        Err(Error::OutOfBounds { x: 0, y: 4, .. })
+    ));
+    assert!(matches!(
+        client.rectangle(250, 0, 10, 1, Rgb::new(1, 1, 1)).await,
+        Err(Error::OutOfBounds { x: 250, .. })
+    ));
+}
+
+#[tokio::test]
+async fn clip_out_of_bounds() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .bounds(Bounds::Clip)
+        .connect(server.address())
+        .await
+        .unwrap();
+    client.pixel(0, 4, Rgb::new(1, 1, 1)).await.unwrap();
+    client
+        .rectangle(6, 3, 9, 9, Rgb::new(2, 2, 2))
+        .await
+        .unwrap();
+    client.flush().await.unwrap();
+
+    let wait = tokio::task::spawn_blocking(move || {
+        assert!(server.wait_for_messages(1, TIMEOUT));
+        server
+    });
+    let server = wait.await.unwrap();
+
+    assert_eq!(server.received(), 1);
+    assert_eq!(server.pixel(5, 3), Some([0, 0, 0]));
+    assert_eq!(server.pixel(6, 3), Some([2, 2, 2]));
+    assert_eq!(server.pixel(7, 3), Some([2, 2, 2]));
+}
+
+#[tokio::test]
+async fn power_budget_dims_fill() {
+    let server = MockServer::start(8, 4).unwrap();
+    let budget = PowerBudget::new(32.0 * 30.0).idle_milliamps_per_pixel(0.0);
+    let client = Client::builder()
+        .power_budget(budget)
+        .connect(server.address())
+        .await
+        .unwrap();
+    assert_eq!(client.estimated_milliamps(), Some(0.0));
+    client.fill(Rgb::WHITE).await.unwrap();
+    client.flush().await.unwrap();
+
+    let wait = tokio::task::spawn_blocking(move || {
+        assert!(server.wait_for_messages(1, TIMEOUT));
+        server
+    });
+    let server = wait.await.unwrap();
+
+    assert_eq!(server.pixel(0, 0), Some([127, 127, 127]));
+    assert!(client.estimated_milliamps().unwrap() <= 32.0 * 30.0);
+}
+
+#[tokio::test]
+async fn frame_loop() {
+    let (server, mut client) = setup().await;
+    let mut frame_loop = FrameLoop::new(1000.0);
+    frame_loop
+        .run(&mut client, async |client, frame| {
+            client.pixel(0, 0, Rgb::new(1, 2, 3)).await?;
+            Ok(if frame.number < 2 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            })
+        })
+        .await
+        .unwrap();
+    let stats = frame_loop.stats();
+    assert_eq!(stats.frames(), 3);
+    assert_eq!(stats.bytes_per_frame(), Some(6.0));
+    assert!(stats.flush_latency(99.0).is_some());
+
+    let wait = tokio::task::spawn_blocking(move || server.wait_for_messages(3, TIMEOUT));
+    assert!(wait.await.unwrap());
+}
+
+#[tokio::test]
+async fn tiled_display() {
+    let left = MockServer::start(8, 4).unwrap();
+    let right = MockServer::start(8, 4).unwrap();
+    let tiles = [
+        Tile::new(left.address().to_string(), 0, 0),
+        Tile::new(right.address().to_string(), 1, 0).rotate(Rotation::Deg180),
+    ];
+    let display = TiledDisplay::connect(&tiles).await.unwrap();
+    assert_eq!((display.width(), display.height()), (16, 4));
+
+    display
+        .rectangle(6, 0, 4, 1, Rgb::new(1, 1, 1))
+        .await
+        .unwrap();
+    display.pixel(15, 3, Rgb::new(2, 2, 2)).await.unwrap();
+    assert!(matches!(
+        display.pixel(16, 0, Rgb::WHITE).await,
+        Err(Error::OutOfBounds { x: 16, .. })
+    ));
+    display.flush().await.unwrap();
+
+    let wait = tokio::task::spawn_blocking(move || {
+        assert!(left.wait_for_messages(1, TIMEOUT));
+        assert!(right.wait_for_messages(2, TIMEOUT));
+        (left, right)
+    });
+    let (left, right) = wait.await.unwrap();
+
+    assert_eq!(left.pixel(5, 0), Some([0, 0, 0]));
+    assert_eq!(left.pixel(6, 0), Some([1, 1, 1]));
+    assert_eq!(left.pixel(7, 0), Some([1, 1, 1]));
+    assert_eq!(right.pixel(7, 3), Some([1, 1, 1]));
+    assert_eq!(right.pixel(6, 3), Some([1, 1, 1]));
+    assert_eq!(right.pixel(5, 3), Some([0, 0, 0]));
+    assert_eq!(right.pixel(0, 0), Some([2, 2, 2]));
+}
+
+#[tokio::test]
+async fn tiled_flush_completes_every_tile() {
+    let broken = MockServer::start(8, 4).unwrap();
+    let working = MockServer::start(8, 4).unwrap();
+    let tiles = [
+        Tile::new(broken.address().to_string(), 0, 0),
+        Tile::new(working.address().to_string(), 1, 0),
+    ];
+    let builder = Client::builder().write_timeout(Duration::from_millis(50));
+    let display = TiledDisplay::connect_with_builder(&tiles, &builder)
+        .await
+        .unwrap();
+
+    broken.stall(true);
+    let frames = many_frames();
+    let first = display.clients()[0].client();
+    for _ in 0..100 {
+        if first.send_all(&frames).await.is_err() {
+            break;
+        }
+    }
+    display.clients()[1].pixel(0, 0, Rgb::WHITE).await.unwrap();
+
+    // The first tile fails right away but the second one is still flushed
+    assert!(display.flush().await.unwrap_err().is_connection_error());
+    let wait = tokio::task::spawn_blocking(move || working.wait_for_messages(1, TIMEOUT));
+    assert!(wait.await.unwrap());
+    broken.stall(false);
+}
+
+#[tokio::test]
+async fn present_framebuffer() {
+    let (server, client) = setup().await;
+    let mut framebuffer = client.framebuffer();
+    framebuffer.fill([0, 0, 64]);
+    framebuffer.set_pixel(7, 3, [255, 0, 0]);
+    let sent = framebuffer.diff().len();
+    client.present(&mut framebuffer).await.unwrap();
+    assert!(framebuffer.diff().is_empty());
+
+    let wait = tokio::task::spawn_blocking(move || {
+        assert!(server.wait_for_messages(sent, TIMEOUT));
+        server
+    });
+    let server = wait.await.unwrap();
+    assert_eq!(server.framebuffer(), framebuffer.pixels());
+}
+
+#[tokio::test]
+async fn reconnecting_client_survives_disconnect() {
+    let server = MockServer::start(8, 4).unwrap();
+    let backoff = Backoff {
+        initial: Duration::from_millis(10),
+        max: Duration::from_millis(50),
+        factor: 2,
+        max_attempts: Some(10),
+    };
+    let mut client = ReconnectingClient::connect(server.address().to_string(), backoff)
+        .await
+        .unwrap();
+
+    server.disconnect_clients();
+    for _ in 0..100 {
+        if server.accepted() > 1 {
+            break;
+        }
+        client.fill(Rgb::new(0, 0, 255)).await.unwrap();
+        client.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(server.accepted(), 2);
+}
+
+#[tokio::test]
+async fn handshake_timeout() {
+    // Accepts connections in the backlog but never sends the handshake
+    let silent = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let error = Client::builder()
+        .handshake_timeout(Duration::from_millis(50))
+        .connect(silent.local_addr().unwrap())
+        .await
+        .err()
+        .unwrap();
+    assert!(error.is_timeout(), "{error:?}");
+}
+
+#[tokio::test]
+async fn connection_refused() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let error = Client::builder()
+        .connect_timeout(TIMEOUT)
+        .connect(address)
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&error, Error::Io(error) if error.kind() == ErrorKind::ConnectionRefused),
+        "{error:?}"
+    );
+}
+
+#[tokio::test]
+async fn failed_write_closes_connection() {
+    let server = MockServer::start(8, 4).unwrap();
+    let client = Client::builder()
+        .write_timeout(Duration::from_millis(50))
+        .connect(server.address())
+        .await
+        .unwrap();
+    server.stall(true);
+    let frames = many_frames();
+    let mut error = None;
+    for _ in 0..100 {
+        if let Err(err) = client.send_all(&frames).await {
+            error = Some(err);
+            break;
+        }
+        if let Err(err) = client.flush().await {
+            error = Some(err);
+            break;
+        }
+    }
+    let error = error.expect("write never timed out");
+    assert!(error.is_timeout(), "{error:?}");
+    server.stall(false);
+
+    // The timed out write might have sent a partial command, nothing is allowed to follow it
+    let error = client.pixel(0, 0, Rgb::WHITE).await.unwrap_err();
+    assert!(error.is_connection_error(), "{error:?}");
+    assert!(client.flush().await.unwrap_err().is_connection_error());
+}